@@ -0,0 +1,121 @@
+use std::{fs, time::Instant};
+
+use clap::Parser;
+use square_1_solver_rust::{square1::Square1, state::{statesqsq::StateSqSq, State}, table::{format_duration, SliceCountTable}};
+
+
+/// Compare generating the sqsq table on the Square-1 with generating it on the move tables
+///
+/// Both tables get written to slice_count_tables under their own names and are removed afterwards.
+#[derive(Parser)]
+struct Cli {
+    /// Shows the progress of the generation
+    #[clap(long, short)]
+    progress: bool
+}
+
+// StateSqSq the way it got generated before the move tables, opening every Position on the Square-1
+struct StateSqSqSquare1(StateSqSq);
+
+impl State for StateSqSqSquare1 {
+    type Config = ();
+
+    const NAME: &str = "sqsq_square1";
+
+    const SIZE: usize = StateSqSq::SIZE;
+
+    const MAX_SLICES: u8 = StateSqSq::MAX_SLICES;
+
+    fn new_with(sq1: Square1, _: &()) -> Self {
+        Self(StateSqSq::new(sq1))
+    }
+
+    fn get_index(&self) -> usize {
+        self.0.get_index()
+    }
+
+    fn get_symmetric_indecies(&mut self) -> Vec<usize> {
+        self.0.get_symmetric_indecies()
+    }
+
+    fn get_square1_num(&self) -> u64 {
+        self.0.get_square1_num()
+    }
+
+    fn gen_next_positions(sq1num: u64) -> Vec<u64> {
+        let base = Square1::from_num(sq1num);
+        base.get_unique_turns_sqsq().map(|turn| {
+            let mut adj = base.clone();
+            adj.turn_layers(&turn);
+            adj.turn_slice().expect("Unique Turns is wrong");
+            adj.get_num()
+        }).to_vec()
+    }
+}
+
+// StateSqSq under another name, so the benchmark doesn't overwrite the sqsq table
+struct StateSqSqCoord(StateSqSq);
+
+impl State for StateSqSqCoord {
+    type Config = ();
+
+    const NAME: &str = "sqsq_coord";
+
+    const SIZE: usize = StateSqSq::SIZE;
+
+    const MAX_SLICES: u8 = StateSqSq::MAX_SLICES;
+
+    fn new_with(sq1: Square1, _: &()) -> Self {
+        Self(StateSqSq::new(sq1))
+    }
+
+    fn get_index(&self) -> usize {
+        self.0.get_index()
+    }
+
+    fn get_symmetric_indecies(&mut self) -> Vec<usize> {
+        self.0.get_symmetric_indecies()
+    }
+
+    fn get_square1_num(&self) -> u64 {
+        self.0.get_square1_num()
+    }
+
+    fn gen_next_positions(num: u64) -> Vec<u64> {
+        StateSqSq::gen_next_positions(num)
+    }
+
+    fn get_position_num(sq1: &Square1) -> u64 {
+        StateSqSq::get_position_num(sq1)
+    }
+
+    fn from_position_num(num: u64, _: &()) -> Self {
+        Self(StateSqSq::from_position_num(num, &()))
+    }
+}
+
+fn main() {
+    let args = Cli::parse();
+
+    let now = Instant::now();
+    let _ = StateSqSq::from_position_num(StateSqSq::gen_next_positions(StateSqSq::get_position_num(&Square1::solved()))[0], &());
+    println!("Built move tables in {}", format_duration(now.elapsed()));
+
+    let now = Instant::now();
+    SliceCountTable::<StateSqSqSquare1>::new(args.progress).generate();
+    let square1_time = now.elapsed();
+
+    let now = Instant::now();
+    SliceCountTable::<StateSqSqCoord>::new(args.progress).generate();
+    let coord_time = now.elapsed();
+
+    let square1_table = fs::read(SliceCountTable::<StateSqSqSquare1>::get_file_name()).expect("Unable to read file");
+    let coord_table = fs::read(SliceCountTable::<StateSqSqCoord>::get_file_name()).expect("Unable to read file");
+    let _ = fs::remove_file(SliceCountTable::<StateSqSqSquare1>::get_file_name());
+    let _ = fs::remove_file(SliceCountTable::<StateSqSqCoord>::get_file_name());
+    assert!(square1_table == coord_table, "The move tables lead to another table");
+
+    println!("Square-1: {}", format_duration(square1_time));
+    println!("Move tables: {}", format_duration(coord_time));
+    println!("Speedup: {:.1}x", square1_time.as_secs_f64() / coord_time.as_secs_f64());
+}
@@ -0,0 +1,80 @@
+use std::{fs, time::Instant};
+
+use clap::{Parser, Subcommand};
+use square_1_solver_rust::{state::stateall::StateAll, table::{compressed::{export_table, get_compressed_file_name, import_table}, format_duration, packed::PackedTable, SliceCountTable}};
+
+
+/// Convert the Slice Count Tables of the Square-1 Solver into other formats
+#[derive(Parser)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Store the slice count modulo 3 in 2 bits
+    Pack,
+    /// Compress a table for distribution
+    Export {
+        /// Name of the table, like all, sqsq or all_packed
+        #[clap(default_value = "all")]
+        table: String
+    },
+    /// Decompress a distributed table
+    Import {
+        /// Name of the table, like all, sqsq or all_packed
+        #[clap(default_value = "all")]
+        table: String
+    }
+}
+
+fn get_table_file_name(table: &str) -> String {
+    format!("slice_count_tables/table_{}.bin", table)
+}
+
+fn get_file_size(file_name: &str) -> String {
+    let bytes = fs::metadata(file_name).map(|metadata| metadata.len()).unwrap_or(0);
+    format!("{:.2} GB", bytes as f64 / 1e9)
+}
+
+fn main() {
+    let args = Cli::parse();
+    match args.command {
+        Command::Pack => {
+            let table = SliceCountTable::<StateAll>::read_table_from_file();
+            println!("Packing Table");
+            let now = Instant::now();
+            let packed = PackedTable::from_table(&table);
+            println!("Finished packing Table in {}", format_duration(now.elapsed()));
+            println!("Saving Table to file");
+            packed.write_table_to_file();
+            println!("{}: {}", SliceCountTable::<StateAll>::get_file_name(), get_file_size(&SliceCountTable::<StateAll>::get_file_name()));
+            println!("{}: {}", PackedTable::get_file_name(), get_file_size(&PackedTable::get_file_name()));
+        }
+        Command::Export {table} => {
+            let file_name = get_table_file_name(&table);
+            let compressed_file_name = get_compressed_file_name(&file_name);
+            println!("Loading Table");
+            let table = fs::read(&file_name).expect("Unable to read file");
+            println!("Compressing Table");
+            let now = Instant::now();
+            export_table(&table, &compressed_file_name, true).expect("Saving Table failed!");
+            println!("Finished compressing Table in {}", format_duration(now.elapsed()));
+            println!("{}: {}", file_name, get_file_size(&file_name));
+            println!("{}: {}", compressed_file_name, get_file_size(&compressed_file_name));
+        }
+        Command::Import {table} => {
+            let file_name = get_table_file_name(&table);
+            let compressed_file_name = get_compressed_file_name(&file_name);
+            println!("Decompressing Table");
+            let now = Instant::now();
+            let table = import_table(&compressed_file_name, true).unwrap_or_else(|error| panic!("Unable to import table: {}", error));
+            println!("Finished decompressing Table in {}", format_duration(now.elapsed()));
+            println!("Saving Table to file");
+            fs::write(&file_name, table).expect("Saving Table failed!");
+            println!("{}: {}", compressed_file_name, get_file_size(&compressed_file_name));
+            println!("{}: {}", file_name, get_file_size(&file_name));
+        }
+    }
+}
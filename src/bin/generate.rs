@@ -1,45 +1,61 @@
 use clap::Parser;
-use square_1_solver_rust::{state::{stateall::StateAll, statecs::StateCS, statesqsq::StateSqSq}, table::SliceCountTable};
+use square_1_solver_rust::{state::{stateall::StateAll, statecs::StateCS, statelin::{StateFB, StateLinCP}, stateorientation::StateOrientation, statepieces::{StateCorners, StateEdges}, statesqsq::StateSqSq, statesubset::{Subset, SubsetConfig, SubsetState}, statevandenbergh::{StateCO, StateCP, StateEO}, State}, table::SliceCountTable};
 
 
 /// Generate Slice Count Table for the Square-1 Solver
 #[derive(Parser)]
 struct Cli {
-    /// The table to generate: cs, sqsq, all
+    /// The table to generate: cs, sqsq, all, corners, edges, orientation, the Vandenbergh steps co, eo, cp, the Lin steps fb, lcp or subset
     table: String,
     /// Disables Progress Output
     #[clap(long, short, action)]
     quiet: bool,
     /// Uses less memory by using files
     #[clap(long, short, action)]
-    limram: bool
+    limram: bool,
+    /// Name of the subset table
+    #[clap(long, default_value = "custom")]
+    name: String,
+    /// Pieces the subset table tells apart, like 8,10,12,14
+    #[clap(long, value_delimiter = ',')]
+    tracked: Vec<u8>,
+    /// Pieces of the subset table where only the layer color matters
+    #[clap(long, value_delimiter = ',')]
+    oriented: Vec<u8>
+}
+
+fn generate<S: State + Sync>(args: &Cli) where S::Config: Default {
+    generate_with::<S>(args, S::Config::default())
+}
+
+fn generate_with<S: State + Sync>(args: &Cli, config: S::Config) {
+    let table = SliceCountTable::<S>::with_config(config, !args.quiet);
+    if args.limram {
+        table.generate_compact();
+    } else {
+        table.generate();
+    }
 }
 
 fn main() {
     let args = Cli::parse();
     match args.table.as_str() {
-        "cs" => {
-            let table = SliceCountTable::<StateCS>::new(!args.quiet);
-            if args.limram {
-                table.generate_compact();
-            } else {
-                table.generate();
-            }
-        }
-        "sqsq" => {
-            let table = SliceCountTable::<StateSqSq>::new(!args.quiet);
-            if args.limram {
-                table.generate_compact();
-            } else {
-                table.generate();
-            }
-        }
-        "all" => {
-            let table = SliceCountTable::<StateAll>::new(!args.quiet);
-            if args.limram {
-                table.generate_compact();
-            } else {
-                table.generate();
+        "cs" => generate::<StateCS>(&args),
+        "sqsq" => generate::<StateSqSq>(&args),
+        "all" => generate::<StateAll>(&args),
+        "corners" => generate::<StateCorners>(&args),
+        "edges" => generate::<StateEdges>(&args),
+        "orientation" => generate::<StateOrientation>(&args),
+        "co" => generate::<StateCO>(&args),
+        "eo" => generate::<StateEO>(&args),
+        "cp" => generate::<StateCP>(&args),
+        "fb" => generate::<StateFB>(&args),
+        "lcp" => generate::<StateLinCP>(&args),
+        "subset" => {
+            let subset = Subset { name: args.name.clone(), tracked: args.tracked.clone(), oriented: args.oriented.clone() };
+            match SubsetConfig::new(subset) {
+                Ok(config) => generate_with::<SubsetState>(&args, config),
+                Err(error) => panic!("{}", error)
             }
         }
         _ => {}
@@ -1,21 +1,162 @@
-use std::time::Instant;
+use std::{fs, time::Instant};
 
-use square_1_solver_rust::{solver::{load_table, solve}, square1::Square1, table::format_duration};
+use clap::{Parser, Subcommand};
+use square_1_solver_rust::{render::RenderOptions, solver::{load_table, solve_with_options, SolveOptions}, square1::Square1, table::format_duration, twophase::solve_cubeshape, ida::{solve_ida, Heuristic}, orientation::solve_orientation, lin::{self, solve_lin}, vandenbergh::{self, solve_vandenbergh}};
 
 
+/// Solve the Square-1 in the fewest slices
+#[derive(Parser)]
+#[command(args_conflicts_with_subcommands = true)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+    /// The position to solve like A1B2C3D4-E5F6G7H8, a random one if left out
+    position: Option<String>,
+    /// Writes a drawing of the position to the SVG file
+    #[clap(long)]
+    svg: Option<String>,
+    /// Prints the position after every step of the solution
+    #[clap(long, short, action)]
+    steps: bool
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// List all slice optimal ways into cube shape using the cs table
+    Cubeshape {
+        /// The position like A1B2C3D4-E5F6G7H8, a random one if left out
+        position: Option<String>,
+        /// Reaches cube shape with even parity
+        #[clap(long, short, action)]
+        parity: bool
+    },
+    /// Solve a cube shape position step by step with the Vandenbergh method
+    Vandenbergh {
+        /// The position like A1B2C3D4-E5F6G7H8, has to be in cube shape with even parity
+        position: String
+    },
+    /// Solve in the fewest slices using the small cs, corners and edges tables
+    Ida {
+        /// The position like A1B2C3D4-E5F6G7H8, a random one if left out
+        position: Option<String>
+    },
+    /// Bring the up colored pieces into the up layer in cube shape, ignoring everything else
+    Orientation {
+        /// The position like A1B2C3D4-E5F6G7H8, a random one if left out
+        position: Option<String>
+    },
+    /// Solve a cube shape position step by step with the Lin method
+    Lin {
+        /// The position like A1B2C3D4-E5F6G7H8, has to be in cube shape with even parity
+        position: String
+    }
+}
+
+fn get_position(position: &Option<String>) -> (Square1, bool) {
+    match position {
+        Some(letters) => match Square1::from_letters(letters) {
+            Ok(position) => position,
+            Err(error) => panic!("{}", error)
+        },
+        None => Square1::scrambled()
+    }
+}
+
 fn main() {
+    let args = Cli::parse();
+    if let Some(Command::Cubeshape { position, parity }) = &args.command {
+        let (square1, bar_solved) = get_position(position);
+        println!("{}", square1.to_letters(bar_solved));
+        let solutions = match solve_cubeshape(square1, *parity) {
+            Ok(solutions) => solutions,
+            Err(error) => panic!("{}", error)
+        };
+        for solution in solutions {
+            println!("{} (Turn Value: {})", solution, solution.turn_value());
+        }
+        return
+    }
+    if let Some(Command::Vandenbergh { position }) = &args.command {
+        let (square1, bar_solved) = get_position(&Some(position.clone()));
+        println!("{}", square1.to_letters(bar_solved));
+        let solutions = match solve_vandenbergh(square1, bar_solved) {
+            Ok(solutions) => solutions,
+            Err(error) => panic!("{}", error)
+        };
+        for (step, solution) in vandenbergh::STEPS.iter().zip(solutions) {
+            println!("{:?}: {} (Turn Value: {})", step, solution, solution.turn_value());
+        }
+        return
+    }
+    if let Some(Command::Ida { position }) = &args.command {
+        let (square1, bar_solved) = get_position(position);
+        println!("{}", square1.to_letters(bar_solved));
+        let heuristic = Heuristic::load();
+        let now = Instant::now();
+        let solution = match solve_ida(square1, bar_solved, &heuristic) {
+            Ok(solution) => solution,
+            Err(error) => panic!("{}", error)
+        };
+        println!("Found solution in {}", format_duration(now.elapsed()));
+        println!("Solution: {}", solution);
+        return
+    }
+    if let Some(Command::Orientation { position }) = &args.command {
+        let (square1, bar_solved) = get_position(position);
+        println!("{}", square1.to_letters(bar_solved));
+        let solution = match solve_orientation(square1) {
+            Ok(solution) => solution,
+            Err(error) => panic!("{}", error)
+        };
+        println!("{} (Turn Value: {})", solution, solution.turn_value());
+        return
+    }
+    if let Some(Command::Lin { position }) = &args.command {
+        let (square1, bar_solved) = get_position(&Some(position.clone()));
+        println!("{}", square1.to_letters(bar_solved));
+        let solutions = match solve_lin(square1, bar_solved) {
+            Ok(solutions) => solutions,
+            Err(error) => panic!("{}", error)
+        };
+        for (step, solution) in lin::STEPS.iter().zip(solutions) {
+            println!("{:?}: {} (Turn Value: {})", step, solution, solution.turn_value());
+        }
+        return
+    }
+
+    let (square1, bar_solved) = get_position(&args.position);
+    println!("{}", square1.to_letters(bar_solved));
+    println!("{}", square1.render_text(true));
+    if let Some(path) = &args.svg {
+        let options = RenderOptions { middle: Some(bar_solved), ..Default::default() };
+        fs::write(path, square1.to_svg(&options)).expect("Couldn't write SVG");
+    }
+
     load_table();
-    // let (square1, bar_solved) = (Square1::from_arr([2, 7, 13, 15, 5, 1, 14, 0, 11, 12, 3, 9, 10, 6, 8, 4]), false);
-    // let (square1, bar_solved) = (Square1::from_arr([0, 5, 2, 1, 4, 3, 6, 7, 9, 8, 11, 10, 13, 12, 15, 14]), false);
-    // let (square1, bar_solved) = (Square1::solved(), false);
-    let (square1, bar_solved) = Square1::scrambled();
-    println!("{square1:?}");
     println!("Solving");
     let now = Instant::now();
-    let solution = solve(square1, bar_solved).expect("Square-1 invalid");
+    let options = SolveOptions { verify: true, ..Default::default() };
+    let solution = match solve_with_options(square1.clone(), bar_solved, &options) {
+        Ok(result) => result.solution,
+        Err(error) => panic!("{}", error)
+    };
     let elapsed = now.elapsed();
     println!("Found solution in {}", format_duration(elapsed));
     println!("Solution: {}", solution);
     println!("Scramble: {}", solution.inverse());
-    println!("{:?}", Square1::from_notation(solution.inverse()));
-}
\ No newline at end of file
+
+    if args.steps {
+        let mut position = square1;
+        let last = solution.notation.len().saturating_sub(1);
+        for (i, &(up, down)) in solution.notation.iter().enumerate() {
+            position.apply_turn((up, down)).expect("Solution was verified");
+            if i < last {
+                position.apply_slice().expect("Solution was verified");
+                println!("({},{})/", up, down);
+            } else {
+                println!("({},{})", up, down);
+            }
+            println!("{}", position.render_text(true));
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+use crate::{solver::{get_abf_turn, Solution, SolveError}, square1::Square1, state::{statecs::StateCS, statepieces::{StateCorners, StateEdges}, State}, table::SliceCountTable};
+
+// Human readable turns, each but the last followed by a slice
+type Path = Vec<(i8, i8)>;
+
+// Maximum over Slice Count Tables of parts of the position, so it never overestimates
+#[derive(Default)]
+pub struct Heuristic {
+    tables: Vec<Box<dyn Fn(Square1) -> u8 + Send + Sync>>
+}
+
+impl Heuristic {
+    // The cs, corners and edges tables, about 7 MB instead of the 1.65 GB of the all table
+    pub fn load() -> Self {
+        Self::default()
+            .with_table::<StateCS>(SliceCountTable::<StateCS>::read_table_from_file())
+            .with_table::<StateCorners>(SliceCountTable::<StateCorners>::read_table_from_file())
+            .with_table::<StateEdges>(SliceCountTable::<StateEdges>::read_table_from_file())
+    }
+
+    pub fn with_table<S: State<Config = ()> + Sync + 'static>(mut self, table: Vec<u8>) -> Self {
+        self.tables.push(Box::new(move |square1| SliceCountTable::<S>::read(&table, S::new(square1).get_index())));
+        self
+    }
+
+    pub fn get_slice_count(&self, square1: &Square1) -> u8 {
+        self.tables.iter().map(|table| table(square1.clone())).max().unwrap_or(0)
+    }
+}
+
+// Slice optimal like solve, but needs less memory. The turn value is not optimised.
+pub fn solve_ida(square1: Square1, bar_solved: bool, heuristic: &Heuristic) -> Result<Solution, SolveError> {
+    square1.validate().map_err(SolveError::Invalid)?;
+    let mut slices = heuristic.get_slice_count(&square1);
+    if slices.is_multiple_of(2) != bar_solved {
+        slices += 1;
+    }
+    loop {
+        if let Some(mut path) = get_ida_path(&square1, slices, heuristic) {
+            path.reverse();
+            return Ok(Solution { notation: path })
+        }
+        // The middle layer only allows every other slice count
+        slices += 2;
+    }
+}
+
+// Finds a path with the given slices, that never gets further away than the Heuristic allows
+fn get_ida_path(square1: &Square1, slices: u8, heuristic: &Heuristic) -> Option<Path> {
+    if slices == 0 {
+        return is_solved_up_to_turns(square1).then(|| vec![square1.get_human_readable(get_abf_turn(square1))])
+    }
+    // get_unique_turns leaves out slicing the other half, that is the same slice followed by
+    // turning the whole puzzle over. Turning over commutes with slices and doesn't change
+    // how many slices are left, the last slice uses get_all_turns and turns it back.
+    let turns: Vec<(usize, usize)> = if slices > 1 {square1.get_unique_turns()} else {square1.get_all_turns()};
+    turns.into_par_iter().find_map_first(|turn| {
+        let mut adj = square1.clone();
+        adj.turn_layers(&turn);
+        adj.turn_slice().expect("Couldn't turn slice");
+        if heuristic.get_slice_count(&adj) < slices {
+            get_ida_path(&adj, slices - 1, heuristic).map(|mut path| {
+                path.push(square1.get_human_readable(turn));
+                path
+            })
+        } else {
+            None
+        }
+    })
+}
+
+// Both layers hold their own pieces in order, so only the last turn is missing
+fn is_solved_up_to_turns(square1: &Square1) -> bool {
+    let (up, down) = (square1.pieces[0], square1.pieces[8]);
+    up < 8 && down > 7 && (0..8).all(|i| {
+        square1.pieces[i] == (up + i as u8) % 8 && square1.pieces[8 + i] == 8 + (down + i as u8) % 8
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{solver::verify_solution, square1::Square1, state::{statepieces::{StateCorners, StateEdges}, State}};
+
+    use super::{is_solved_up_to_turns, solve_ida, Heuristic};
+
+    #[test]
+    pub fn test_ida() {
+        let mut turned = Square1::solved();
+        assert!(is_solved_up_to_turns(&turned));
+        turned.turn_layers(&(1, 6));
+        assert!(is_solved_up_to_turns(&turned));
+        let mut sliced = Square1::solved();
+        sliced.turn_slice().unwrap();
+        assert!(!is_solved_up_to_turns(&sliced));
+
+        // The corners don't see the edges and the other way around
+        let swapped_corners = Square1::from_arr([2, 1, 0, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+        let swapped_edges = Square1::from_arr([0, 3, 2, 1, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+        let solved = Square1::solved();
+        assert_eq!(StateCorners::new(swapped_edges.clone()).get_index(), StateCorners::new(solved.clone()).get_index());
+        assert_ne!(StateCorners::new(swapped_corners.clone()).get_index(), StateCorners::new(solved.clone()).get_index());
+        assert_eq!(StateEdges::new(swapped_corners).get_index(), StateEdges::new(solved.clone()).get_index());
+        assert_ne!(StateEdges::new(swapped_edges).get_index(), StateEdges::new(solved).get_index());
+
+        // Without tables the search is breadth first, which still finds short solutions
+        let heuristic = Heuristic::default();
+        let mut square1 = Square1::solved();
+        square1.turn_layers(&(1, 0));
+        square1.turn_slice().unwrap();
+        let solution = solve_ida(square1.clone(), false, &heuristic).unwrap();
+        assert_eq!(solution.slice_count(), 1);
+        assert_eq!(verify_solution(square1.clone(), false, &solution), Ok(()));
+        let solution = solve_ida(square1.clone(), true, &heuristic).unwrap();
+        assert_eq!(solution.slice_count(), 2);
+        assert_eq!(verify_solution(square1, true, &solution), Ok(()));
+    }
+}
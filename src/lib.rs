@@ -1,4 +1,13 @@
 pub mod square1;
 pub mod state;
 pub mod table;
-pub mod solver;
\ No newline at end of file
+pub mod solver;
+pub mod twophase;
+pub mod stickers;
+pub mod render;
+pub mod shape;
+pub mod parity;
+pub mod vandenbergh;
+pub mod lin;
+pub mod ida;
+pub mod orientation;
\ No newline at end of file
@@ -0,0 +1,154 @@
+use std::{collections::HashSet, sync::LazyLock};
+
+use crate::{solver::{Solution, SolveError}, square1::Square1, state::{statelin::{StateFB, StateLinCP}, statesqsq::StateSqSq, State}, table::SliceCountTable, vandenbergh::{check_cube_shape, solve_finish, solve_substep}};
+
+static FB_TABLE: LazyLock<Vec<u8>> = LazyLock::new(SliceCountTable::<StateFB>::read_table_from_file);
+static LCP_TABLE: LazyLock<Vec<u8>> = LazyLock::new(SliceCountTable::<StateLinCP>::read_table_from_file);
+
+// The down edge that is left for the last slot
+const LAST_SLOT: u8 = 15;
+
+// In the order they get solved
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Step {
+    FirstBlock,
+    CornerPermutation,
+    // Also turns the layers into place, which needs even parity
+    LastFiveEdges
+}
+
+pub const STEPS: [Step; 3] = [Step::FirstBlock, Step::CornerPermutation, Step::LastFiveEdges];
+
+impl Step {
+    // Slices still needed for the step, only used before LastFiveEdges
+    fn get_count(self, square1: Square1) -> u8 {
+        match self {
+            Step::FirstBlock => SliceCountTable::<StateFB>::read(&FB_TABLE, StateFB::new(square1).get_index()),
+            Step::CornerPermutation => SliceCountTable::<StateLinCP>::read(&LCP_TABLE, StateLinCP::new(square1).get_index()),
+            Step::LastFiveEdges => unreachable!("LastFiveEdges uses the sqsq table")
+        }
+    }
+
+    // Index of the case, positions with the same index are solved by the same algorithm
+    fn get_case_index(self, square1: Square1) -> usize {
+        match self {
+            Step::FirstBlock => StateFB::new(square1).get_index(),
+            Step::CornerPermutation => StateLinCP::new(square1).get_index(),
+            Step::LastFiveEdges => StateSqSq::new(square1).get_index()
+        }
+    }
+}
+
+pub fn load_tables() {
+    SliceCountTable::<StateFB>::read(&FB_TABLE, 0);
+    SliceCountTable::<StateLinCP>::read(&LCP_TABLE, 0);
+}
+
+// Works like vandenbergh::solve_step, only LastFiveEdges turns the layers into place
+pub fn solve_step(square1: &Square1, step: Step, bar_solved: bool) -> Result<Solution, SolveError> {
+    check_cube_shape(square1)?;
+    if step == Step::LastFiveEdges {
+        solve_finish(square1, bar_solved)
+    } else {
+        Ok(solve_substep(square1, &|square1| step.get_count(square1)))
+    }
+}
+
+pub fn solve_lin(square1: Square1, bar_solved: bool) -> Result<Vec<Solution>, SolveError> {
+    let mut square1 = square1;
+    let mut bar_solved = bar_solved;
+    let mut solutions = vec![];
+    for step in STEPS {
+        let solution = solve_step(&square1, step, bar_solved)?;
+        square1.apply(&solution).expect("Step Solution can't be applied");
+        bar_solved ^= solution.slice_count() % 2 == 1;
+        solutions.push(solution);
+    }
+    Ok(solutions)
+}
+
+// One position per case with the earlier steps solved, leaving out odd parity for LastFiveEdges
+pub fn get_cases(step: Step) -> Vec<Square1> {
+    let positions: Vec<Square1> = match step {
+        Step::FirstBlock => {
+            let corners: Vec<u8> = (0..8).map(|i| i * 2).collect();
+            let edges: Vec<u8> = (0..8).map(|i| i * 2 + 1).collect();
+            let corner_orders = get_orders(&corners, &[8, 10, 12, 14]);
+            let edge_orders = get_orders(&edges, &[9, 11, 13]);
+            corner_orders.iter().flat_map(|corners| edge_orders.iter().map(|edges| Square1::from_permutations(corners, edges))).collect()
+        }
+        Step::CornerPermutation => {
+            let edges: Vec<u8> = (0..8).map(|i| i * 2 + 1).collect();
+            get_orders(&[0, 2, 4, 6], &[0, 2, 4, 6]).iter().map(|up| {
+                Square1::from_permutations(&[up.as_slice(), &[8, 10, 12, 14]].concat(), &edges)
+            }).collect()
+        }
+        Step::LastFiveEdges => {
+            let corners: Vec<u8> = (0..8).map(|i| i * 2).collect();
+            get_orders(&[1, 3, 5, 7, LAST_SLOT], &[1, 3, 5, 7, LAST_SLOT]).iter().map(|free| {
+                Square1::from_permutations(&corners, &[&free[..4], &[9, 11, 13], &free[4..]].concat())
+            }).collect()
+        }
+    };
+
+    let mut seen = HashSet::new();
+    positions.into_iter().filter(|square1| {
+        (step != Step::LastFiveEdges || square1.parity(true).overall == Some(false)) && seen.insert(step.get_case_index(square1.clone()))
+    }).collect()
+}
+
+// All ways to place the moving pieces in the slots, the other pieces fill the rest in order
+fn get_orders(pieces: &[u8], moving: &[u8]) -> Vec<Vec<u8>> {
+    let mut orders = vec![];
+    place_pieces(pieces, moving, &mut vec![None; pieces.len()], &mut orders);
+    orders
+}
+
+fn place_pieces(pieces: &[u8], moving: &[u8], order: &mut Vec<Option<u8>>, orders: &mut Vec<Vec<u8>>) {
+    let Some((&piece, moving)) = moving.split_first() else {
+        let placed: Vec<u8> = order.iter().flatten().copied().collect();
+        let mut rest = pieces.iter().filter(|other| !placed.contains(other));
+        orders.push(order.iter().map(|slot| slot.unwrap_or_else(|| *rest.next().unwrap())).collect());
+        return
+    };
+    // Starts with the slot the piece is solved in, so the solved case comes first
+    let solved_slot = pieces.iter().position(|&other| other == piece).expect("Moving piece is missing");
+    for offset in 0..order.len() {
+        let slot = (solved_slot + offset) % order.len();
+        if order[slot].is_none() {
+            order[slot] = Some(piece);
+            place_pieces(pieces, moving, order, orders);
+            order[slot] = None;
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use crate::{square1::Square1, state::{statelin::{StateFB, StateLinCP}, State}};
+
+    use super::{get_cases, Step};
+
+    #[test]
+    pub fn test_lin() {
+        // Turning a layer keeps both indices, swapping two corners only changes the permutation
+        let solved = Square1::solved();
+        let mut turned = solved.clone();
+        turned.turn_layers(&(2, 0));
+        assert_eq!(StateFB::new(solved.clone()).get_index(), StateFB::new(turned.clone()).get_index());
+        assert_eq!(StateLinCP::new(solved.clone()).get_index(), StateLinCP::new(turned).get_index());
+        let swapped = Square1::from_arr([2, 1, 0, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+        assert_eq!(StateFB::new(solved.clone()).get_index(), StateFB::new(swapped.clone()).get_index());
+        assert_ne!(StateLinCP::new(solved.clone()).get_index(), StateLinCP::new(swapped).get_index());
+
+        let cases = get_cases(Step::CornerPermutation);
+        assert_eq!(cases.len(), 6);
+        assert_eq!(cases[0], solved);
+        assert!(cases.iter().all(|case| StateFB::new(case.clone()).get_index() == StateFB::new(solved.clone()).get_index()));
+
+        let cases = get_cases(Step::LastFiveEdges);
+        assert_eq!(cases.len(), 60);
+        assert!(cases.iter().all(|case| StateLinCP::new(case.clone()).get_index() == StateLinCP::new(solved.clone()).get_index()));
+    }
+}
@@ -0,0 +1,76 @@
+use std::sync::LazyLock;
+
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+use crate::{solver::{get_length, Solution, SolveError}, square1::Square1, state::{stateorientation::StateOrientation, State}, table::SliceCountTable};
+
+static ORIENTATION_TABLE: LazyLock<Vec<u8>> = LazyLock::new(SliceCountTable::<StateOrientation>::read_table_from_file);
+
+// Human readable turns, each followed by a slice
+type Path = Vec<(i8, i8)>;
+
+pub fn load_table() {
+    SliceCountTable::<StateOrientation>::read(&ORIENTATION_TABLE, 0);
+}
+
+fn get_orientation_count(square1: Square1) -> u8 {
+    let index = StateOrientation::new(square1).get_index();
+    SliceCountTable::<StateOrientation>::read(&ORIENTATION_TABLE, index)
+}
+
+// Cube shape with the up colored pieces in the up layer, ignoring the middle layer
+pub fn solve_orientation(square1: Square1) -> Result<Solution, SolveError> {
+    square1.validate().map_err(SolveError::Invalid)?;
+    let slices = get_orientation_count(square1.clone());
+    let (_, mut notation) = get_orientation_path(&square1, slices).expect("Slice Count Table is wrong");
+    if !notation.is_empty() {
+        notation.push((0, 0));
+    }
+    Ok(Solution { notation })
+}
+
+// Finds the path with the lowest turn value, the table only reduces by layer turns, so all turns are needed
+fn get_orientation_path(square1: &Square1, slices: u8) -> Option<(u32, Path)> {
+    if slices == 0 {
+        return Some((0, vec![]))
+    }
+    square1.get_all_turns().into_par_iter().filter_map(|turn| {
+        let mut adj = square1.clone();
+        adj.turn_layers(&turn);
+        adj.turn_slice().expect("Couldn't turn slice");
+        if get_orientation_count(adj.clone()) < slices {
+            get_orientation_path(&adj, slices - 1).map(|(value, mut path)| {
+                let readable = square1.get_human_readable(turn);
+                path.insert(0, readable);
+                (value + get_length(readable) as u32, path)
+            })
+        } else {
+            None
+        }
+    }).min_by_key(|(value, _)| *value)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{square1::Square1, state::{stateorientation::StateOrientation, State}};
+
+    #[test]
+    pub fn test_orientation() {
+        let index = |square1: Square1| StateOrientation::new(square1).get_index();
+        let solved = Square1::solved();
+        let mut turned = solved.clone();
+        turned.turn_layers(&(2, 4));
+        assert_eq!(index(turned), index(solved.clone()));
+
+        // Only the colors count, not which piece has them
+        let swapped_up = Square1::from_arr([2, 3, 0, 1, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+        assert_eq!(index(swapped_up), index(solved.clone()));
+        let swapped_colors = Square1::from_arr([0, 9, 2, 3, 4, 5, 6, 7, 8, 1, 10, 11, 12, 13, 14, 15]);
+        assert_ne!(index(swapped_colors), index(solved.clone()));
+
+        let mut sliced = solved.clone();
+        sliced.turn_slice().unwrap();
+        assert_ne!(index(sliced.clone()), index(solved));
+        assert!(index(sliced) < StateOrientation::SIZE);
+    }
+}
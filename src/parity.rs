@@ -0,0 +1,103 @@
+use crate::{square1::Square1, state::{statecs::StateCS, State}};
+
+// The cube shape classes with 4 corners in each layer and some with 5 and 3 corners come first,
+// only these have a parity in the cs Slice Count Table
+const CUBESHAPES_WITH_PARITY: usize = 48;
+
+// true means odd
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Parity {
+    // Only in cube shape
+    pub corners: Option<bool>,
+    pub edges: Option<bool>,
+    // Outside of cube shape this compares to the canonical orientation of the shape class in the cs Slice Count Table,
+    // shape classes that reach both parities by turning the whole puzzle have none
+    pub overall: Option<bool>,
+    pub odd_slices: bool,
+}
+
+impl Square1 {
+    // Each layer gets read from its first corner, so turning a layer by 90° changes the corner and edge parity, but not the overall one
+    pub fn parity(&self, bar_solved: bool) -> Parity {
+        let state = StateCS::new(self.clone());
+        let odd_slices = !bar_solved;
+        if state.get_cubeshape() != 0 {
+            let overall = (state.get_cubeshape() < CUBESHAPES_WITH_PARITY).then(|| state.get_parity() != get_solved_parity());
+            return Parity { corners: None, edges: None, overall, odd_slices }
+        }
+
+        let divide = self.get_divide();
+        let mut pieces = vec![];
+        for layer in [&self.pieces[..divide], &self.pieces[divide..]] {
+            let first_corner = layer.iter().position(|&piece| piece & 1 == 0).unwrap_or(0);
+            pieces.extend_from_slice(&layer[first_corner..]);
+            pieces.extend_from_slice(&layer[..first_corner]);
+        }
+        let corners = get_inversion_parity(pieces.iter().filter(|&&piece| piece & 1 == 0));
+        let edges = get_inversion_parity(pieces.iter().filter(|&&piece| piece & 1 == 1));
+        Parity { corners: Some(corners), edges: Some(edges), overall: Some(corners != edges), odd_slices }
+    }
+}
+
+// The cs parity of solved, which has even parity
+fn get_solved_parity() -> usize {
+    StateCS::new(Square1::solved()).get_parity()
+}
+
+fn get_inversion_parity<'a>(pieces: impl Iterator<Item = &'a u8>) -> bool {
+    let pieces: Vec<u8> = pieces.copied().collect();
+    let mut inversions = 0;
+    for i in 0..pieces.len() {
+        for j in i + 1..pieces.len() {
+            if pieces[i] > pieces[j] {
+                inversions += 1;
+            }
+        }
+    }
+    inversions % 2 == 1
+}
+
+#[cfg(test)]
+mod test {
+    use rand::seq::SliceRandom;
+
+    use crate::{square1::Square1, state::{statecs::StateCS, State}};
+
+    #[test]
+    pub fn test_parity() {
+        let parity = Square1::solved().parity(true);
+        assert_eq!((parity.corners, parity.edges, parity.overall, parity.odd_slices), (Some(false), Some(false), Some(false), false));
+
+        let swapped = Square1::from_arr([2, 1, 0, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+        let parity = swapped.parity(false);
+        assert_eq!((parity.corners, parity.edges, parity.overall, parity.odd_slices), (Some(true), Some(false), Some(true), true));
+
+        // Turning the up layer by 30° and 90° keeps the overall parity
+        let turned = Square1::from_arr([1, 2, 3, 4, 5, 6, 7, 0, 8, 9, 10, 11, 12, 13, 14, 15]);
+        assert_eq!(turned.parity(true).overall, Some(false));
+        let turned = Square1::from_arr([2, 3, 4, 5, 6, 7, 0, 1, 8, 9, 10, 11, 12, 13, 14, 15]);
+        assert_eq!(turned.parity(true).overall, Some(false));
+        assert_eq!(turned.parity(true).corners, Some(true));
+
+        // The overall parity in cube shape agrees with the cs Slice Count Table
+        let mut rng = rand::rng();
+        let solved_parity = StateCS::new(Square1::solved()).get_index() / 65;
+        for _ in 0..20 {
+            let mut corners: Vec<u8> = (0..8).map(|i| i * 2).collect();
+            let mut edges: Vec<u8> = (0..8).map(|i| i * 2 + 1).collect();
+            corners.shuffle(&mut rng);
+            edges.shuffle(&mut rng);
+            let square1 = Square1::from_permutations(&corners, &edges);
+            let cs_parity = StateCS::new(square1.clone()).get_index() / 65;
+            assert_eq!(square1.parity(true).overall, Some(cs_parity != solved_parity));
+        }
+
+        // Outside of cube shape swapping two pieces still changes the parity
+        let mut sliced = Square1::solved();
+        sliced.turn_slice().unwrap();
+        let mut swapped = sliced.clone();
+        swapped.pieces.swap(0, 2);
+        assert_eq!(sliced.parity(true).corners, None);
+        assert_ne!(sliced.parity(true).overall, swapped.parity(true).overall);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,174 @@
+use std::fmt::{self, Write};
+
+use crate::{square1::Square1, stickers::{ColorScheme, Face, PIECE_SIDES}};
+
+// Half the side of a layer's square, relative to the size of its drawing
+const LAYER_SCALE: f64 = 0.3;
+// How far the side stickers reach beyond the top and bottom stickers
+const SIDE_SCALE: f64 = 1.2;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RenderOptions {
+    // Up, down, front, right, back and left
+    pub colors: [String; 6],
+    // Width and height of each layer in pixels
+    pub size: u32,
+    // Solved for Some(true) and flipped for Some(false)
+    pub middle: Option<bool>,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        let colors = ["#ffffff", "#ffd500", "#009b48", "#b71234", "#0046ad", "#ff5800"];
+        Self { colors: colors.map(String::from), size: 200, middle: None }
+    }
+}
+
+impl RenderOptions {
+    fn get_color(&self, face: Face) -> &str {
+        &self.colors[face as usize]
+    }
+}
+
+impl Square1 {
+    // The bottom layer is seen from the bottom, so the front is at its top
+    pub fn to_svg(&self, options: &RenderOptions) -> String {
+        let size = options.size as f64;
+        let bar_height = if options.middle.is_some() {size / 5.0} else {0.0};
+        let mut svg = String::new();
+        write!(svg, r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {} {}">"#,
+            size * 2.0, size + bar_height, size * 2.0, size + bar_height).unwrap();
+
+        let divide = self.get_divide();
+        let mut angle = 0;
+        for i in 0..16 {
+            if i == divide {
+                angle = 0;
+            }
+            let up = i < divide;
+            // Angles grow clockwise on screen, the top layer starts at the front cut and the bottom layer at the back cut
+            let center = if up {(size / 2.0, size / 2.0)} else {(size * 1.5, size / 2.0)};
+            let direction = |units: f64| if up {90.0 + (units + 0.5) * 30.0} else {90.0 - (units + 0.5) * 30.0};
+            self.draw_piece(&mut svg, options, self.pieces[i], center, angle as f64, &direction);
+            angle += self.get_angle(i);
+        }
+
+        if let Some(bar_solved) = options.middle {
+            // The middle layer seen from the front, the right half shows the back when flipped
+            let right = if bar_solved {Face::Front} else {Face::Back};
+            let (left_x, width, y) = (size * 0.2, size * 0.8, size + bar_height * 0.2);
+            write_rect(&mut svg, left_x, y, width, bar_height * 0.6, options.get_color(Face::Front));
+            write_rect(&mut svg, left_x + width, y, width, bar_height * 0.6, options.get_color(right));
+        }
+        svg.push_str("</svg>");
+        svg
+    }
+
+    fn draw_piece(&self, svg: &mut String, options: &RenderOptions, piece: u8, center: (f64, f64), start: f64, direction: &dyn Fn(f64) -> f64) {
+        let side = options.size as f64 * LAYER_SCALE;
+        let point = |units: f64, radius: f64| {
+            let radians = direction(units).to_radians();
+            (center.0 + radius * radians.cos(), center.1 + radius * radians.sin())
+        };
+        // Edges end where the sides of the square are 15° away from the middle of a side
+        let edge_radius = side / 15f64.to_radians().cos();
+        let corner_radius = side * 2f64.sqrt();
+        let layer = if piece < 8 {Face::Up} else {Face::Down};
+        let sides = PIECE_SIDES[piece as usize];
+
+        let outline = if piece.is_multiple_of(2) {
+            vec![(start, edge_radius), (start + 1.0, corner_radius), (start + 2.0, edge_radius)]
+        } else {
+            vec![(start, edge_radius), (start + 1.0, edge_radius)]
+        };
+        let mut points = vec![center];
+        points.extend(outline.iter().map(|&(units, radius)| point(units, radius)));
+        write_polygon(svg, &points, options.get_color(layer));
+
+        for (i, &face) in sides.iter().enumerate() {
+            let (from, to) = (outline[i], outline[i + 1]);
+            let points = [point(from.0, from.1), point(to.0, to.1), point(to.0, to.1 * SIDE_SCALE), point(from.0, from.1 * SIDE_SCALE)];
+            write_polygon(svg, &points, options.get_color(face));
+        }
+    }
+}
+
+impl Square1 {
+    // Lists the pieces clockwise from the cut, a | marks where the slice can go through
+    pub fn render_text(&self, ansi: bool) -> String {
+        let scheme = ColorScheme::default();
+        let divide = self.get_divide();
+        let mut text = String::from("U:");
+        let mut angle = 0;
+        for i in 0..16 {
+            if i == divide {
+                text.push_str("\nD:");
+                angle = 0;
+            } else if angle == 6 {
+                text.push_str(" |");
+            }
+            text.push(' ');
+            for color in scheme.get_stickers(self.pieces[i]) {
+                if ansi {
+                    write!(text, "\x1b[30;48;5;{}m{}\x1b[0m", get_ansi_color(color), color).unwrap();
+                } else {
+                    text.push(color);
+                }
+            }
+            angle += self.get_angle(i);
+        }
+        text
+    }
+}
+
+impl fmt::Display for Square1 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.render_text(false))
+    }
+}
+
+// 256 color code of the default color scheme's colors
+fn get_ansi_color(color: char) -> u8 {
+    match color {
+        'W' => 231,
+        'Y' => 226,
+        'G' => 34,
+        'R' => 160,
+        'B' => 27,
+        'O' => 208,
+        _ => 244
+    }
+}
+
+fn write_polygon(svg: &mut String, points: &[(f64, f64)], color: &str) {
+    let points: Vec<String> = points.iter().map(|(x, y)| format!("{:.1},{:.1}", x, y)).collect();
+    write!(svg, r#"<polygon points="{}" fill="{}" stroke="black"/>"#, points.join(" "), color).unwrap();
+}
+
+fn write_rect(svg: &mut String, x: f64, y: f64, width: f64, height: f64, color: &str) {
+    write!(svg, r#"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" fill="{}" stroke="black"/>"#, x, y, width, height, color).unwrap();
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{render::RenderOptions, square1::Square1};
+
+    #[test]
+    pub fn test_text() {
+        assert_eq!(Square1::solved().to_string(), "U: WGO WO WOB WB | WBR WR WRG WG\nD: YBR YR YRG YG | YGO YO YOB YB");
+        let misaligned = Square1::from_arr([1, 0, 2, 4, 3, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+        assert_eq!(misaligned.to_string(), "U: WO WGO WOB WBR WB WR WRG WG\nD: YBR YR YRG YG | YGO YO YOB YB");
+        assert!(misaligned.render_text(true).contains("\x1b[30;48;5;231mW\x1b[0m"));
+    }
+
+    #[test]
+    pub fn test_svg() {
+        let options = RenderOptions { middle: Some(true), ..Default::default() };
+        let svg = Square1::solved().to_svg(&options);
+        assert!(svg.starts_with("<svg") && svg.ends_with("</svg>"));
+        // 16 pieces with 24 side stickers and 2 halves of the middle layer
+        assert_eq!(svg.matches("<polygon").count(), 40);
+        assert_eq!(svg.matches("<rect").count(), 2);
+        assert_eq!(svg.matches("#ffffff").count(), 8);
+    }
+}
@@ -0,0 +1,199 @@
+use std::{collections::VecDeque, fmt};
+
+use crate::{square1::Square1, state::{statecs::StateCS, State}};
+
+// Community names of the layers, by the edges after each corner
+// Layers with 2 and 3 corners are named by these edges, leaving out the zeros at the end
+const LAYER_NAMES: [(&[usize], &str); 29] = [
+    (&[0, 0, 0, 0, 0, 0], "Star"),
+    (&[2, 0, 0, 0, 0], "Pawn"),
+    (&[1, 1, 0, 0, 0], "Muffin"),
+    (&[1, 0, 1, 0, 0], "Tulip"),
+    (&[1, 1, 1, 1], "Square"),
+    (&[2, 2, 0, 0], "Kite"),
+    (&[2, 0, 2, 0], "Barrel"),
+    (&[2, 1, 0, 1], "Shield"),
+    (&[3, 0, 1, 0], "Mushroom"),
+    (&[4, 0, 0, 0], "Scallop"),
+    (&[3, 1, 0, 0], "Left Fist"),
+    (&[3, 0, 0, 1], "Right Fist"),
+    (&[2, 1, 1, 0], "Left Paw"),
+    (&[2, 0, 1, 1], "Right Paw"),
+    (&[6, 0, 0], "6"),
+    (&[5, 1, 0], "5-1"),
+    (&[5, 0, 1], "5-0-1"),
+    (&[4, 2, 0], "4-2"),
+    (&[4, 0, 2], "4-0-2"),
+    (&[4, 1, 1], "4-1-1"),
+    (&[3, 3, 0], "3-3"),
+    (&[3, 2, 1], "3-2-1"),
+    (&[3, 1, 2], "3-1-2"),
+    (&[2, 2, 2], "2-2-2"),
+    (&[8, 0], "8"),
+    (&[7, 1], "7-1"),
+    (&[6, 2], "6-2"),
+    (&[5, 3], "5-3"),
+    (&[4, 4], "4-4"),
+];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LayerShape {
+    // Edges after each corner, rotated to be the largest in lexicographic order
+    pub gaps: Vec<usize>,
+}
+
+impl LayerShape {
+    fn new(pieces: &[u8]) -> Self {
+        let mut gaps = vec![];
+        for &piece in pieces {
+            if piece & 1 == 0 {
+                gaps.push(0);
+            } else if let Some(gap) = gaps.last_mut() {
+                *gap += 1;
+            }
+        }
+        // Edges before the first corner belong to the last one
+        let leading = pieces.iter().take_while(|&&piece| piece & 1 == 1).count();
+        if let Some(gap) = gaps.last_mut() {
+            *gap += leading;
+        }
+        let gaps = (0..gaps.len())
+            .map(|shift| [&gaps[shift..], &gaps[..shift]].concat())
+            .max()
+            .unwrap_or_default();
+        Self { gaps }
+    }
+
+    pub fn get_corner_count(&self) -> usize {
+        self.gaps.len()
+    }
+
+    pub fn get_name(&self) -> Option<&'static str> {
+        LAYER_NAMES.iter().find(|(gaps, _)| gaps[..] == self.gaps[..]).map(|&(_, name)| name)
+    }
+}
+
+impl fmt::Display for LayerShape {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.get_name() {
+            Some(name) => write!(f, "{}", name),
+            None => write!(f, "{} corners {:?}", self.gaps.len(), self.gaps)
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Shape {
+    pub up: LayerShape,
+    pub down: LayerShape,
+    // Shape class up to turning, flipping and mirroring
+    pub cubeshape: usize,
+    // Index in the cs Slice Count Table, which also tells the parity
+    pub cs_index: usize,
+    pub is_cube: bool,
+}
+
+impl fmt::Display for Shape {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} / {}", self.up, self.down)
+    }
+}
+
+impl Square1 {
+    pub fn shape(&self) -> Shape {
+        let divide = self.get_divide();
+        let up = LayerShape::new(&self.pieces[..divide]);
+        // The bottom layer is seen from the bottom, so it gets read the other way
+        let mut down_pieces = self.pieces[divide..].to_vec();
+        down_pieces.reverse();
+        let down = LayerShape::new(&down_pieces);
+
+        let state = StateCS::new(self.clone());
+        let is_cube = state.get_cubeshape() == 0;
+        Shape { up, down, cubeshape: state.get_cubeshape(), cs_index: state.get_index(), is_cube }
+    }
+}
+
+// One position of each index of the cs Slice Count Table, the first 65 are the cube shape classes with parity 0
+pub fn get_shape_classes() -> Vec<Square1> {
+    let mut classes: Vec<Option<Square1>> = vec![None; StateCS::SIZE];
+    let mut queue = VecDeque::from([Square1::solved().get_num()]);
+    classes[StateCS::new(Square1::solved()).get_index()] = Some(Square1::solved());
+    while let Some(num) = queue.pop_front() {
+        for next in StateCS::gen_next_positions(num) {
+            let square1 = Square1::from_num(next);
+            let index = StateCS::new(square1.clone()).get_index();
+            if classes[index].is_none() {
+                classes[index] = Some(square1);
+                queue.push_back(next);
+            }
+        }
+    }
+    classes.into_iter().map(|class| class.expect("Shape class is unreachable")).collect()
+}
+
+pub fn get_shapes() -> Vec<Shape> {
+    get_shape_classes().iter().take(65).map(Square1::shape).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use crate::{shape::{get_shape_classes, get_shapes, LayerShape, LAYER_NAMES}, square1::Square1};
+
+    #[test]
+    pub fn test_shape() {
+        let shape = Square1::solved().shape();
+        assert!(shape.is_cube);
+        assert_eq!(shape.cubeshape, 0);
+        assert_eq!(shape.to_string(), "Square / Square");
+
+        let mut sliced = Square1::solved();
+        sliced.turn_slice().unwrap();
+        let shape = sliced.shape();
+        assert!(!shape.is_cube);
+        assert_eq!(shape.to_string(), "Shield / Shield");
+
+        let shape = Square1::from_arr([0, 1, 3, 5, 2, 7, 4, 9, 11, 6, 13, 8, 10, 12, 14, 15]).shape();
+        assert_eq!(shape.to_string(), "3-1-2 / Muffin");
+    }
+
+    #[test]
+    pub fn test_layer_names() {
+        // Every order of corners and edges that fills a layer, so all rotations and mirrors
+        let mut layers: Vec<Vec<u8>> = vec![vec![]];
+        let mut found = HashSet::new();
+        while let Some(layer) = layers.pop() {
+            let angle: usize = layer.iter().map(|&piece| 2 - (piece & 1) as usize).sum();
+            // There are only 8 edges
+            if layer.iter().filter(|&&piece| piece == 1).count() > 8 {
+                continue
+            }
+            if angle == 12 {
+                let shape = LayerShape::new(&layer);
+                assert!(shape.get_name().is_some(), "{:?} has no name", shape.gaps);
+                found.insert(shape.gaps);
+            } else if angle < 12 {
+                layers.push([&layer[..], &[1]].concat());
+                if angle < 11 {
+                    layers.push([&layer[..], &[0]].concat());
+                }
+            }
+        }
+        assert_eq!(found.len(), LAYER_NAMES.len());
+    }
+
+    #[test]
+    pub fn test_classes() {
+        let classes = get_shape_classes();
+        assert_eq!(classes.len(), 113);
+        for (index, square1) in classes.iter().enumerate() {
+            assert_eq!(square1.shape().cs_index, index);
+        }
+        let shapes = get_shapes();
+        assert_eq!(shapes.iter().filter(|shape| shape.up.get_name() == Some("Star")).count(), 3);
+        assert!(shapes.iter().enumerate().all(|(index, shape)| shape.cubeshape == index));
+        assert!(shapes.iter().all(|shape| shape.up.get_name().is_some() && shape.down.get_name().is_some()));
+    }
+}
@@ -1,20 +1,45 @@
-use std::{cmp::{max, min}, fmt, iter::Peekable, str::FromStr, sync::LazyLock};
+use std::{cmp::{max, min}, fmt, str::FromStr, sync::LazyLock};
 
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
 
-use crate::{square1::Square1, state::{stateall::StateAll, State}, table::SliceCountTable};
+use crate::{square1::{SequenceError, Square1, ValidationError}, state::{stateall::StateAll, State}, table::{packed::PackedTable, SliceCountTable}};
 
-static TABLE: LazyLock<Vec<u8>> = std::sync::LazyLock::new(|| SliceCountTable::<StateAll>::read_table_from_file());
+static TABLE: LazyLock<AllTable> = std::sync::LazyLock::new(|| {
+    if SliceCountTable::<StateAll>::file_exists() {
+        AllTable::Full(SliceCountTable::<StateAll>::read_table_from_file())
+    } else {
+        AllTable::Packed(PackedTable::read_table_from_file())
+    }
+});
+
+// The full table if there is one, otherwise the packed one
+enum AllTable {
+    Full(Vec<u8>),
+    Packed(PackedTable)
+}
 
 pub fn load_table() {
-    SliceCountTable::<StateAll>::read(&TABLE, 0);
+    LazyLock::force(&TABLE);
 }
 
 fn get_slice_count(square1: Square1) -> u8 {
-    let index = StateAll::new(square1).get_index();
-    SliceCountTable::<StateAll>::read(&TABLE, index)
+    match &*TABLE {
+        AllTable::Full(table) => SliceCountTable::<StateAll>::read(table, StateAll::new(square1).get_index()),
+        AllTable::Packed(table) => table.get_slice_count(square1)
+    }
+}
+
+// Slice count of a Position one slice away from a Position with the given slice count
+fn get_next_slice_count(square1: Square1, count: u8) -> u8 {
+    match &*TABLE {
+        AllTable::Packed(table) => table.get_next_slice_count(square1, count),
+        _ => get_slice_count(square1)
+    }
 }
 
+// Whether the last odd and even turn got swapped, the turn value and the path in reverse
+type BestPath = (bool, bool, u8, Vec<(i8, i8)>);
+
 struct Step {
     readable: (i8, i8),
     next_steps: Vec<Self>,
@@ -22,34 +47,33 @@ struct Step {
 }
 
 impl Step {
-    fn new(readable: (i8, i8), square1: Square1, slices: i8) -> Self {
+    // The count is the exact slice count of the Position, slices are the ones left to solve it
+    fn new(readable: (i8, i8), square1: Square1, count: u8, slices: i8, width: usize) -> Self {
         let next_steps: Vec<Self> = if slices > 0 {
             let turns: Vec<(usize, usize)> = if slices > 1 {square1.get_unique_turns()} else {square1.get_all_turns()};
-            turns.into_par_iter().filter_map(|turn| {
+            // Finds the turns, that get closer to solved
+            let candidates: Vec<((usize, usize), Square1, u8)> = turns.into_par_iter().filter_map(|turn| {
                 let mut adj = square1.clone();
                 adj.turn_layers(&turn);
                 adj.turn_slice().expect("Couldn't turn slice");
-                if get_slice_count(adj.clone()) < slices as u8 {
-                    let next_step = Self::new(square1.get_human_readable(turn), adj, slices - 1);
-                    if slices == 1 {
-                        if next_step.next_steps.is_empty() {
-                            None
-                        } else {
-                            Some(next_step)
-                        }
-                    } else {
-                        Some(next_step)
-                    }
+                let adj_count = get_next_slice_count(adj.clone(), count);
+                // On the last slice, the top layer also has to end up on top
+                if adj_count < slices as u8 && (slices > 1 || adj.pieces[0] < 8) {
+                    Some((turn, adj, adj_count))
                 } else {
                     None
                 }
+            }).collect();
+            // Only explores the first few turns, if the width is limited
+            candidates.into_iter().take(width).collect::<Vec<_>>().into_par_iter().map(|(turn, adj, adj_count)| {
+                Self::new(square1.get_human_readable(turn), adj, adj_count, slices - 1, width)
             }).collect()
         } else if slices == 0 {
             if square1.pieces[0] < 8 {
-                let abf_turn: (usize, usize) = (8 - square1.pieces[0] as usize, 16 - square1.pieces[8] as usize);
+                let abf_turn: (usize, usize) = get_abf_turn(&square1);
                 let mut adj = square1.clone();
                 adj.turn_layers(&abf_turn);
-                vec![Self::new(square1.get_human_readable(abf_turn), adj, -1)]
+                vec![Self::new(square1.get_human_readable(abf_turn), adj, count, -1, width)]
             } else {
                 vec![]
             }
@@ -63,35 +87,36 @@ impl Step {
         }
     }
 
-    fn get_best_path(&self) -> Vec<(i8, i8)> {
-        let (_, _, length, mut path) = self.get_best_path_raw(6, 6);
+    // None if every explored branch ends in a dead end
+    fn get_best_path(&self) -> Option<Vec<(i8, i8)>> {
+        let (_, _, length, mut path) = self.get_best_path_raw(6, 6)?;
         println!("Turn Value: {}", length);
         path.pop();
         path.reverse();
-        path
+        Some(path)
     }
 
-    fn get_best_path_raw(&self, mut pot_swap_odd: u8, mut pot_swap_even: u8) -> (bool, bool, u8, Vec<(i8, i8)>) {
+    fn get_best_path_raw(&self, mut pot_swap_odd: u8, mut pot_swap_even: u8) -> Option<BestPath> {
         if self.slices == -1 {
             let length = get_length(self.readable);
             let length_swap = get_length_swap(self.readable);
             if pot_swap_odd + length_swap < length {
-                (true, false, length_swap, vec![get_swap(self.readable)])
+                Some((true, false, length_swap, vec![get_swap(self.readable)]))
             } else {
-                (false, false, length, vec![self.readable])
+                Some((false, false, length, vec![self.readable]))
             }
         } else if self.slices == 0 {
-            let (swap_odd, _, total_length, mut path) = self.next_steps[0].get_best_path_raw(pot_swap_odd, pot_swap_even);
+            let (swap_odd, _, total_length, mut path) = self.next_steps.first()?.get_best_path_raw(pot_swap_odd, pot_swap_even)?;
             let new_readable = if swap_odd {get_onhead(self.readable)} else {self.readable};
 
             let length = get_length(new_readable);
             let length_swap = get_length_swap(new_readable);
             if pot_swap_even + length_swap < length {
                 path.push(get_swap(new_readable));
-                (swap_odd, true, total_length + length_swap, path)
+                Some((swap_odd, true, total_length + length_swap, path))
             } else {
                 path.push(new_readable);
-                (swap_odd, false, total_length + length, path)
+                Some((swap_odd, false, total_length + length, path))
             }
         } else {
             let length = get_length(self.readable);
@@ -111,129 +136,151 @@ impl Step {
                     pot_swap_even = new_swap;
                 }
             }
-            match self.next_steps.iter().map(|step| step.get_best_path_raw(pot_swap_odd, pot_swap_even)).min_by_key(|(_, _, total_length, _)| *total_length) {
-                Some((best_swap_odd, best_swap_even, best_total_length, mut best_path)) => {
-                    if odd {
-                        let new_readable = if best_swap_even {get_onhead(self.readable)} else {self.readable};
-                        if best_swap_odd && swap_is_better {
-                            best_path.push(get_swap(new_readable));
-                            (false, best_swap_even, best_total_length + length_swap, best_path)
-                        } else {
-                            best_path.push(new_readable);
-                            (best_swap_odd, best_swap_even, best_total_length + length, best_path)
-                        }
-                    } else {
-                        let new_readable = if best_swap_odd {get_onhead(self.readable)} else {self.readable};
-                        if best_swap_even && swap_is_better {
-                            best_path.push(get_swap(new_readable));
-                            (best_swap_odd, false, best_total_length + length_swap, best_path)
-                        } else {
-                            best_path.push(new_readable);
-                            (best_swap_odd, best_swap_even, best_total_length + length, best_path)
-                        }
-                    }
+            // Dead ends of a limited search are left out
+            let (best_swap_odd, best_swap_even, best_total_length, mut best_path) = self.next_steps.iter()
+                .filter_map(|step| step.get_best_path_raw(pot_swap_odd, pot_swap_even))
+                .min_by_key(|(_, _, total_length, _)| *total_length)?;
+            if odd {
+                let new_readable = if best_swap_even {get_onhead(self.readable)} else {self.readable};
+                if best_swap_odd && swap_is_better {
+                    best_path.push(get_swap(new_readable));
+                    Some((false, best_swap_even, best_total_length + length_swap, best_path))
+                } else {
+                    best_path.push(new_readable);
+                    Some((best_swap_odd, best_swap_even, best_total_length + length, best_path))
+                }
+            } else {
+                let new_readable = if best_swap_odd {get_onhead(self.readable)} else {self.readable};
+                if best_swap_even && swap_is_better {
+                    best_path.push(get_swap(new_readable));
+                    Some((best_swap_odd, false, best_total_length + length_swap, best_path))
+                } else {
+                    best_path.push(new_readable);
+                    Some((best_swap_odd, best_swap_even, best_total_length + length, best_path))
                 }
-                None => (false, false, 85, vec![])
             }
         }
     }
 }
 
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Solution {
     pub notation: Vec<(i8, i8)>
 }
 
 impl FromStr for Solution {
     type Err = ();
-    
+
+    // Reads every notation style, the first and last turn may be left out
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut c = s.chars()
-            .filter(|x|!x.is_whitespace())
-            .peekable();
-        let mut solution = match c.peek() {
-            Some('(') => Self::parse_tuple(c)?,
-            Some('/') => {
-                c.next();
-                Self::parse_tuple(c)
-                    .map(|mut s|{
-                        s.notation.push((0, 0));
-                        s
-                    })?
-            },
-            _ => return Err(()),
-        };
-        solution.notation.reverse();
-        Ok(solution)
+        let s: String = s.chars().filter(|x| !x.is_whitespace()).collect();
+        if s.is_empty() {
+            return Ok(Solution { notation: vec![] })
+        }
+        let parts: Vec<&str> = s.split('/').collect();
+        let last = parts.len() - 1;
+        let notation = parts.iter().enumerate().map(|(i, part)| {
+            if part.is_empty() && (i == 0 || i == last) && last > 0 {
+                Ok((0, 0))
+            } else {
+                Self::parse_turn(part)
+            }
+        }).collect::<Result<_, _>>()?;
+        Ok(Solution { notation })
     }
 }
 
 impl Solution {
-    fn parse_tuple(mut c: Peekable<impl Iterator<Item = char>>) -> Result<Solution, ()> {
-        if c.next() != Some('(') {
-            return Err(());
-        }
-        let a = Self::parse_num(&mut c)?;
-        if c.next() != Some(',') {
-            return Err(());
+    fn parse_turn(part: &str) -> Result<(i8, i8), ()> {
+        if !part.is_empty() && !part.starts_with('(') && !part.contains(',') {
+            return Self::parse_karnaukh(part)
         }
-        let b = Self::parse_num(&mut c)?;
-        if c.next() != Some(')') {
-            return Err(());
-        }
-        Solution::parse_slash(c)
-                .map(|mut s|{
-                    s.notation.push((a, b));
-                    s
-                })
+        let inner = match part.strip_prefix('(') {
+            Some(rest) => rest.strip_suffix(')').ok_or(())?,
+            None => part,
+        };
+        let (up, down) = inner.split_once(',').ok_or(())?;
+        Ok((up.parse().map_err(|_| ())?, down.parse().map_err(|_| ())?))
     }
 
-    fn parse_slash(mut c: Peekable<impl Iterator<Item = char>>) -> Result<Solution, ()> {
-        match c.next() {
-            Some('/') => {
-                if c.peek().is_none() {
-                    Ok(Solution {
-                        notation: vec![(0, 0)],
-                    })
-                } else {
-                    Self::parse_tuple(c)   
+    // Adds up the Karnaukh moves of one turn, like U'd2
+    fn parse_karnaukh(part: &str) -> Result<(i8, i8), ()> {
+        let mut c = part.chars().peekable();
+        let (mut up, mut down) = (0i8, 0i8);
+        while let Some(letter) = c.next() {
+            let &(_, (mut move_up, mut move_down)) = KARNAUKH_MOVES.iter().find(|(token, _)| *token == letter).ok_or(())?;
+            while let Some(&suffix) = c.peek() {
+                match suffix {
+                    '2' => (move_up, move_down) = (move_up.checked_mul(2).ok_or(())?, move_down.checked_mul(2).ok_or(())?),
+                    '\'' => (move_up, move_down) = (move_up.checked_neg().ok_or(())?, move_down.checked_neg().ok_or(())?),
+                    _ => break,
                 }
-            },
-            None => Ok(Solution {
-                notation: vec![],
-            }),
-            _ => Err(())
+                c.next();
+            }
+            (up, down) = (up.checked_add(move_up).ok_or(())?, down.checked_add(move_down).ok_or(())?);
         }
-        
+        Ok((up, down))
     }
 
-    
+    // Finds the Karnaukh move for the turn, or falls back to the tuple
+    fn get_karnaukh(readable: (i8, i8)) -> String {
+        for (factor, suffix) in [(1, ""), (-1, "'"), (2, "2"), (-2, "2'")] {
+            if let Some((token, _)) = KARNAUKH_MOVES.iter().find(|(_, (up, down))| (up * factor, down * factor) == readable) {
+                return format!("{}{}", token, suffix)
+            }
+        }
+        format!("({},{})", readable.0, readable.1)
+    }
 
-    fn parse_num<'a>(c: &mut Peekable<impl Iterator<Item = char>>) -> Result<i8, ()> {
-        match c.next() {
-            Some('-') => Self::parse_num(c).map(|x|-x),
-            Some(c) => i8::from_str(c.to_string().as_str()).map_err(|_|()),
-            _ => Err(())
+    // Leaves out an empty first or last turn
+    pub fn format(&self, style: NotationStyle) -> String {
+        let last = self.notation.len().saturating_sub(1);
+        let mut sequence = String::new();
+        for (i, &(up, down)) in self.notation.iter().enumerate() {
+            if i > 0 {
+                sequence.push_str(match style {
+                    NotationStyle::Compact => "/",
+                    NotationStyle::Wca => "/ ",
+                    NotationStyle::Spaced | NotationStyle::Karnaukh => " / ",
+                });
+            }
+            if (up, down) != (0, 0) || (i != 0 && i != last) || last == 0 {
+                match style {
+                    NotationStyle::Compact => sequence.push_str(&format!("{},{}", up, down)),
+                    NotationStyle::Karnaukh => sequence.push_str(&Self::get_karnaukh((up, down))),
+                    _ => sequence.push_str(&format!("({},{})", up, down)),
+                }
+            }
         }
+        sequence.trim().to_string()
     }
 }
 
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum NotationStyle {
+    // 1,0/-1,2/
+    Compact,
+    // (1,0)/ (-1,2)/
+    #[default]
+    Wca,
+    // (1,0) / (-1,2) /
+    Spaced,
+    // U / D' / (1,0)
+    Karnaukh,
+}
+
+// A ' inverts a move and a 2 doubles it, moves written together add up
+pub const KARNAUKH_MOVES: [(char, (i8, i8)); 10] = [
+    ('U', (3, 0)), ('D', (0, 3)),
+    ('u', (2, -1)), ('d', (-1, 2)),
+    ('E', (3, -3)), ('e', (3, 3)),
+    ('F', (4, 1)), ('f', (1, 4)),
+    ('M', (1, 1)), ('m', (2, 2)),
+];
+
 impl fmt::Display for Solution {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let mut sequence = String::new();
-        if self.notation[0] != (0, 0) || self.notation.len() < 2 {
-            sequence.push_str(&format!(" ({},{})", self.notation[0].0, self.notation[0].1));
-        }
-        for i in 1..self.notation.len()-1 {
-            sequence.push('/');
-            sequence.push_str(&format!(" ({},{})", self.notation[i].0, self.notation[i].1));
-        }
-        if self.notation.len() > 1 {
-            sequence.push('/');
-            if self.notation[self.notation.len() - 1] != (0, 0) {
-                sequence.push_str(&format!(" ({},{})", self.notation[self.notation.len() - 1].0, self.notation[self.notation.len() - 1].1));
-            }
-        }
-        write!(f, "{}", sequence)
+        write!(f, "{}", self.format(NotationStyle::Wca))
     }
 }
 
@@ -243,28 +290,283 @@ impl Solution {
         notation.reverse();
         Self { notation: notation.iter().map(|(u, d)| (-u, -d)).collect() }
     }
+
+    // Combines the turns where the sequences meet
+    pub fn concat(&self, other: &Self) -> Self {
+        let (Some(&last), Some(&first)) = (self.notation.last(), other.notation.first()) else {
+            return Self { notation: [&self.notation[..], &other.notation[..]].concat() }
+        };
+        // Normalised turns can't overflow when added
+        let ((up, down), (other_up, other_down)) = (normalise_turn(last), normalise_turn(first));
+        let mut notation = self.notation[..self.notation.len() - 1].to_vec();
+        notation.push(normalise_turn((up + other_up, down + other_down)));
+        notation.extend_from_slice(&other.notation[1..]);
+        Self { notation }
+    }
+
+    // Does the same on a Square-1 mirrored with mirror_layers
+    pub fn mirror(&self) -> Self {
+        let mut notation: Vec<(i8, i8)> = self.notation.iter().map(|&turn| {
+            let (up, down) = normalise_turn(turn);
+            (-up, -down)
+        }).collect();
+        // Mirroring moves the slice to the other half, so it gets turned back at both ends
+        if let [first, .., last] = &mut notation[..] {
+            *first = normalise_turn((first.0 + 6, first.1 + 6));
+            *last = normalise_turn((last.0 + 6, last.1 + 6));
+        }
+        Self { notation: notation.into_iter().map(normalise_turn).collect() }
+    }
+
+    // Does the same on a Square-1 flipped with flip_layers
+    pub fn flip(&self) -> Self {
+        Self { notation: self.notation.iter().map(|&(up, down)| (down, up)).collect() }
+    }
+
+    pub fn conjugate(&self, setup: &Self) -> Self {
+        setup.concat(self).concat(&setup.inverse())
+    }
+
+    pub fn commutator(a: &Self, b: &Self) -> Self {
+        a.concat(b).concat(&a.inverse()).concat(&b.inverse())
+    }
+
+    // Two slices around a (0,0) cancel, so that the turns before and after them combine
+    pub fn simplify(&self) -> Self {
+        let mut notation: Vec<(i8, i8)> = self.notation.iter().map(|&turn| normalise_turn(turn)).collect();
+        let mut index = 1;
+        while index + 1 < notation.len() {
+            if notation[index] == (0, 0) {
+                let (up, down) = notation.remove(index + 1);
+                notation.remove(index);
+                let previous = &mut notation[index - 1];
+                *previous = normalise_turn((previous.0 + up, previous.1 + down));
+                // The merged turn might now cancel the slices around it
+                index = index.saturating_sub(1).max(1);
+            } else {
+                index += 1;
+            }
+        }
+        Self { notation }
+    }
+
+    pub fn equivalent(&self, other: &Self) -> bool {
+        self.equivalent_on(other, &Square1::solved())
+    }
+
+    pub fn equivalent_on(&self, other: &Self, square1: &Square1) -> bool {
+        let mut this = square1.clone();
+        let mut that = square1.clone();
+        this.apply(self).is_ok() && that.apply(other).is_ok() && this == that
+            && self.slice_count() % 2 == other.slice_count() % 2
+    }
+
+    pub fn slice_count(&self) -> usize {
+        self.notation.len().saturating_sub(1)
+    }
+
+    pub fn turn_value(&self) -> u32 {
+        self.notation.iter().map(|&readable| get_length(readable) as u32).sum()
+    }
+
+    // Every slice and every turn other than (0,0) counts once
+    pub fn twist_count(&self) -> usize {
+        self.slice_count() + self.notation.iter().filter(|&&readable| readable != (0, 0)).count()
+    }
 }
 
-pub fn solve(square1: Square1, bar_solved: bool) -> Result<Solution, ()> {
-    if !square1.is_valid() {
-        Err(())
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SolveOptions {
+    pub mode: SolveMode,
+    pub record_states: bool,
+    pub verify: bool
+}
+
+pub struct SolveResult {
+    pub solution: Solution,
+    pub slice_count: usize,
+    pub turn_value: u32,
+    pub twist_count: usize,
+    // Whether an additional slice was needed to solve the middle layer
+    pub bar_fixed: bool,
+    // The position after every slice with its slice count
+    pub states: Option<Vec<(Square1, u8)>>
+}
+
+impl SolveResult {
+    fn new(square1: Square1, solution: Solution, bar_fixed: bool, record_states: bool) -> Self {
+        let states = if record_states {
+            let mut count = get_slice_count(square1.clone());
+            let mut state = square1;
+            let states = solution.notation.iter().take(solution.slice_count()).map(|turn| {
+                state.apply_turn(*turn).expect("Solution contains bad turns");
+                state.apply_slice().expect("Solution not sliceable");
+                count = get_next_slice_count(state.clone(), count);
+                (state.clone(), count)
+            }).collect();
+            Some(states)
+        } else {
+            None
+        };
+        Self {
+            slice_count: solution.slice_count(),
+            turn_value: solution.turn_value(),
+            twist_count: solution.twist_count(),
+            solution,
+            bar_fixed,
+            states
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SolveMode {
+    #[default]
+    Optimal,
+    // Skips the turn value optimisation
+    Fast,
+    // Explores at most this many turns per slice
+    Hybrid(usize)
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SolveError {
+    Invalid(ValidationError),
+    NotCubeShape,
+    // Slices in cube shape can't fix odd parity
+    OddParity,
+    Verification(VerifyError)
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SolveError::Invalid(error) => write!(f, "Square-1 is invalid: {}", error),
+            SolveError::NotCubeShape => write!(f, "Square-1 is not in cube shape"),
+            SolveError::OddParity => write!(f, "Square-1 has odd parity"),
+            SolveError::Verification(error) => write!(f, "Solution failed verification: {}", error)
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum VerifyError {
+    Move(SequenceError),
+    NotSolved(Square1),
+    // Everything is solved, except for the middle layer
+    MiddleLayer
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerifyError::Move(error) => write!(f, "{}", error),
+            VerifyError::NotSolved(square1) => write!(f, "ends in {:?} instead of solved", square1),
+            VerifyError::MiddleLayer => write!(f, "middle layer ends unsolved")
+        }
+    }
+}
+
+pub fn verify_solution(square1: Square1, bar_solved: bool, solution: &Solution) -> Result<(), VerifyError> {
+    let mut square1 = square1;
+    square1.apply(solution).map_err(VerifyError::Move)?;
+    if square1 != Square1::solved() {
+        Err(VerifyError::NotSolved(square1))
+    } else if bar_solved != solution.slice_count().is_multiple_of(2) {
+        Err(VerifyError::MiddleLayer)
+    } else {
+        Ok(())
+    }
+}
+
+pub fn solve(square1: Square1, bar_solved: bool) -> Result<Solution, SolveError> {
+    solve_with_mode(square1, bar_solved, SolveMode::Optimal)
+}
+
+pub fn solve_with_mode(square1: Square1, bar_solved: bool, mode: SolveMode) -> Result<Solution, SolveError> {
+    solve_with_options(square1, bar_solved, &SolveOptions { mode, ..Default::default() }).map(|result| result.solution)
+}
+
+pub fn solve_with_options(square1: Square1, bar_solved: bool, options: &SolveOptions) -> Result<SolveResult, SolveError> {
+    if let Err(error) = square1.validate() {
+        Err(SolveError::Invalid(error))
     } else {
-        let mut slices = get_slice_count(square1.clone());
+        let count = get_slice_count(square1.clone());
+        let mut slices = count;
+        let mut bar_fixed = false;
         if (slices % 2 == 0) == bar_solved {
             if slices == 0 && square1.pieces[0] > 7 {
                 slices = 2;
             }
         } else if slices == 0 {
             slices = 3;
+            bar_fixed = true;
         } else {
             slices += 1;
+            bar_fixed = true;
         }
         println!("Solvable in {} slices", slices);
-        let origin = Step::new((0, 0), square1, slices as i8);
-        Ok(Solution{ notation: origin.get_best_path() })
+        let notation = match options.mode {
+            SolveMode::Optimal => Step::new((0, 0), square1.clone(), count, slices as i8, usize::MAX).get_best_path().expect("Slice Count Table is wrong"),
+            SolveMode::Fast => get_greedy_path(square1.clone(), count, slices),
+            SolveMode::Hybrid(width) => {
+                // The limited search can run into dead ends only
+                Step::new((0, 0), square1.clone(), count, slices as i8, width.max(1)).get_best_path()
+                    .unwrap_or_else(|| get_greedy_path(square1.clone(), count, slices))
+            }
+        };
+        let solution = Solution{ notation };
+        if options.verify {
+            verify_solution(square1.clone(), bar_solved, &solution).map_err(SolveError::Verification)?;
+        }
+        Ok(SolveResult::new(square1, solution, bar_fixed, options.record_states))
+    }
+}
+
+fn get_greedy_path(square1: Square1, count: u8, slices: u8) -> Vec<(i8, i8)> {
+    let mut path = get_greedy_path_raw(&square1, count, slices, &get_next_slice_count).expect("Slice Count Table is wrong");
+    path.reverse();
+    println!("Turn Value: {}", path.iter().map(|&readable| get_length(readable) as u32).sum::<u32>());
+    path
+}
+
+// Walks down the table, backtracking only if the first turn leads into a dead end
+fn get_greedy_path_raw(square1: &Square1, count: u8, slices: u8, get_count: &impl Fn(Square1, u8) -> u8) -> Option<Vec<(i8, i8)>> {
+    if slices == 0 {
+        if square1.pieces[0] < 8 {
+            Some(vec![square1.get_human_readable(get_abf_turn(square1))])
+        } else {
+            None
+        }
+    } else {
+        let turns: Vec<(usize, usize)> = if slices > 1 {square1.get_unique_turns()} else {square1.get_all_turns()};
+        turns.into_iter().find_map(|turn| {
+            let mut adj = square1.clone();
+            adj.turn_layers(&turn);
+            adj.turn_slice().expect("Couldn't turn slice");
+            let adj_count = get_count(adj.clone(), count);
+            if adj_count < slices {
+                get_greedy_path_raw(&adj, adj_count, slices - 1, get_count).map(|mut path| {
+                    path.push(square1.get_human_readable(turn));
+                    path
+                })
+            } else {
+                None
+            }
+        })
     }
 }
 
+pub(crate) fn get_abf_turn(square1: &Square1) -> (usize, usize) {
+    (8 - square1.pieces[0] as usize, 16 - square1.pieces[8] as usize)
+}
+
+pub(crate) fn normalise_turn(readable: (i8, i8)) -> (i8, i8) {
+    let up = (readable.0.rem_euclid(12) + 5) % 12 - 5;
+    let down = (readable.1.rem_euclid(12) + 5) % 12 - 5;
+    (up, down)
+}
+
 fn get_onhead(readable: (i8, i8)) -> (i8, i8) {
     (readable.1, readable.0)
 }
@@ -281,7 +583,7 @@ fn get_swap(readable: (i8, i8)) -> (i8, i8) {
     (up, down)
 }
 
-fn get_length(readable: (i8, i8)) -> u8 {
+pub(crate) fn get_length(readable: (i8, i8)) -> u8 {
     max(readable.0.abs(), readable.1.abs()) as u8
 }
 
@@ -289,14 +591,223 @@ fn get_length_swap(readable: (i8, i8)) -> u8 {
     6 - min(readable.0.abs(), readable.1.abs()) as u8
 }
 
+#[cfg(test)]
 mod test {
     use std::str::FromStr;
 
-    use crate::solver::Solution;
+    use rand::Rng;
+
+    use crate::{solver::{get_abf_turn, get_greedy_path_raw, solve_with_mode, verify_solution, NotationStyle, Solution, SolveMode, VerifyError}, square1::{SequenceError, Square1}};
 
     #[test]
     pub fn test_parse() {
         let s = Solution::from_str("(2,3)/(-2,5)/(6,0)/(0,1)").unwrap();
         println!("{s}");
+        assert_eq!(s.notation, vec![(2, 3), (-2, 5), (6, 0), (0, 1)]);
+        assert_eq!(Solution::from_str("/ 1,0/-11, 10 /").unwrap().notation, vec![(0, 0), (1, 0), (-11, 10), (0, 0)]);
+        assert!(Solution::from_str("(1,0)//(2,0)").is_err());
+        assert!(Solution::from_str("(1,0/").is_err());
     }
-}
\ No newline at end of file
+
+    #[test]
+    pub fn test_karnaukh() {
+        let s = Solution::from_str("/ U' / D2 / u d' / E'e2'/").unwrap();
+        assert_eq!(s.notation, vec![(0, 0), (-3, 0), (0, 6), (3, -3), (-9, -3), (0, 0)]);
+        assert_eq!(Solution::from_str("/ U / (1,0) / m").unwrap().notation, vec![(0, 0), (3, 0), (1, 0), (2, 2)]);
+        assert!(Solution::from_str("/ U / x /").is_err());
+        // Turns too large for the notation
+        assert!(Solution::from_str(&"m2".repeat(40)).is_err());
+        assert!(Solution::from_str("U222222").is_err());
+        assert!(Solution::from_str("m'222222'").is_err());
+        assert_eq!(Solution::from_str(&"m2".repeat(31)).unwrap().notation, vec![(124, 124)]);
+
+        let s = Solution { notation: vec![(0, 0), (3, 0), (-3, 3), (1, 0), (-6, 0), (0, 0)] };
+        assert_eq!(s.format(NotationStyle::Karnaukh), "/ U / E' / (1,0) / U2' /");
+        assert_eq!(Solution::from_str(&s.format(NotationStyle::Karnaukh)), Ok(s));
+    }
+
+    #[test]
+    pub fn test_format() {
+        let s = Solution::from_str("(1,0)/(-1,2)/").unwrap();
+        assert_eq!(s.format(NotationStyle::Compact), "1,0/-1,2/");
+        assert_eq!(s.format(NotationStyle::Wca), "(1,0)/ (-1,2)/");
+        assert_eq!(s.format(NotationStyle::Spaced), "(1,0) / (-1,2) /");
+        assert_eq!(Solution::from_str("/").unwrap().to_string(), "/");
+
+        let solutions = ["", "(0,0)", "(3,-3)", "/", "/(3,0)", "(0,-1)/", "/(0,0)/", "(1,0)/(0,0)/(-12,11)/(6,6)"];
+        for style in [NotationStyle::Compact, NotationStyle::Wca, NotationStyle::Spaced, NotationStyle::Karnaukh] {
+            for s in solutions.map(|s| Solution::from_str(s).unwrap()) {
+                assert_eq!(Solution::from_str(&s.format(style)), Ok(s));
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_verify() {
+        let solution = Solution::from_str("(0,-2)/(2,0)/(-1,4)/(-3,0)/(0,-1)/(-2,4)/(0,3)/(-1,2)/(-3,-3)/(-5,-2)/(-4,-3)").unwrap();
+        let square1 = Square1::from_notation(solution.inverse()).unwrap();
+        assert_eq!(verify_solution(square1.clone(), true, &solution), Ok(()));
+        assert_eq!(verify_solution(square1.clone(), false, &solution), Err(VerifyError::MiddleLayer));
+
+        let mut shortened = Solution { notation: solution.notation.clone() };
+        shortened.notation.pop();
+        assert!(matches!(verify_solution(square1.clone(), true, &shortened), Err(VerifyError::NotSolved(_))));
+
+        shortened.notation[2] = (1, 4);
+        assert!(matches!(verify_solution(square1, true, &shortened), Err(VerifyError::Move(SequenceError { index: 2, .. }))));
+    }
+
+    #[test]
+    pub fn test_simplify() {
+        let mut square1 = Square1::solved();
+        square1.apply(&Solution::from_str("(0,-2)/(2,0)/(-1,4)/(-3,0)/(0,-1)/(-2,4)/(0,3)/(-1,2)/(-3,-3)/(-5,-2)/(-4,-3)").unwrap().inverse()).unwrap();
+        let s = Solution { notation: vec![(0, -14), (0, 0), (0, 12), (0, 0), (-12, 0), (2, 0), (-1, 4), (0, 0)] };
+        assert_eq!(s.simplify().notation, vec![(0, -2), (2, 0), (-1, 4), (0, 0)]);
+        assert!(s.equivalent_on(&s.simplify(), &square1));
+
+        let s = Solution { notation: vec![(0, 0), (0, 0), (0, 0), (3, -12)] };
+        assert_eq!(s.simplify().notation, vec![(0, 0), (3, 0)]);
+        assert!(s.equivalent(&s.simplify()));
+        assert!(!s.equivalent(&Solution::from_str("/(0,3)").unwrap()));
+
+        // Turns close to the limits of i8 don't overflow
+        let s = Solution { notation: vec![(127, -128)] };
+        assert_eq!(s.simplify().notation, vec![(-5, 4)]);
+    }
+
+    // Random sequence of turns and slices, that is possible on the given position
+    fn random_sequence(square1: &Square1, slices: usize) -> Solution {
+        let mut rng = rand::rng();
+        let mut square1 = square1.clone();
+        let mut notation = Vec::new();
+        for i in 0..=slices {
+            let turns = square1.get_all_turns();
+            let turn = turns[rng.random_range(0..turns.len())];
+            notation.push(square1.get_human_readable(turn));
+            square1.turn_layers(&turn);
+            if i < slices {
+                square1.turn_slice().unwrap();
+            }
+        }
+        Solution { notation }
+    }
+
+    fn applied(square1: &Square1, sequence: &Solution) -> Square1 {
+        let mut square1 = square1.clone();
+        square1.apply(sequence).unwrap();
+        square1
+    }
+
+    #[test]
+    pub fn test_mirror_flip() {
+        for slices in 0..20 {
+            let (square1, _) = Square1::scrambled();
+            let sequence = random_sequence(&square1, slices);
+
+            let mut mirrored = square1.clone();
+            mirrored.mirror_layers();
+            let mut expected = applied(&square1, &sequence);
+            expected.mirror_layers();
+            assert_eq!(applied(&mirrored, &sequence.mirror()), expected);
+
+            let mut flipped = square1.clone();
+            flipped.flip_layers();
+            let mut expected = applied(&square1, &sequence);
+            expected.flip_layers();
+            assert_eq!(applied(&flipped, &sequence.flip()), expected);
+        }
+        assert_eq!(Solution { notation: vec![(-128, 127)] }.mirror().notation, vec![(-4, 5)]);
+    }
+
+    #[test]
+    pub fn test_concat() {
+        for slices in 0..20 {
+            let (square1, _) = Square1::scrambled();
+            let a = random_sequence(&square1, slices);
+            let middle = applied(&square1, &a);
+            let b = random_sequence(&middle, slices / 2);
+            let end = applied(&middle, &b);
+            assert_eq!(applied(&square1, &a.concat(&b)), end);
+            assert_eq!(a.concat(&b).slice_count(), a.slice_count() + b.slice_count());
+        }
+        let large = Solution { notation: vec![(0, 0), (100, -127)] };
+        assert_eq!(large.concat(&large.inverse()).notation, vec![(0, 0), (0, 0), (0, 0)]);
+        assert_eq!(Solution { notation: vec![(100, 0)] }.concat(&Solution { notation: vec![(100, 0)] }).notation, vec![(-4, 0)]);
+
+        // Commutators and conjugates are only possible on some positions, so they get searched
+        let square1 = Square1::solved();
+        let mut found = 0;
+        while found < 10 {
+            let a = random_sequence(&square1, 1);
+            let b = random_sequence(&applied(&square1, &a), 1);
+            let mut steps = square1.clone();
+            if [&a, &b, &a.inverse(), &b.inverse()].into_iter().all(|sequence| steps.apply(sequence).is_ok()) {
+                assert_eq!(applied(&square1, &Solution::commutator(&a, &b)), steps);
+                let mut steps = square1.clone();
+                steps.apply(&a).unwrap();
+                steps.apply(&b).unwrap();
+                steps.apply(&a.inverse()).unwrap();
+                assert_eq!(applied(&square1, &b.conjugate(&a)), steps);
+                found += 1;
+            }
+        }
+        let empty = Solution { notation: vec![] };
+        let turn = Solution::from_str("(3,0)").unwrap();
+        assert_eq!(empty.concat(&turn).notation, turn.notation);
+        assert_eq!(turn.concat(&turn).notation, vec![(6, 0)]);
+    }
+
+    #[test]
+    pub fn test_metrics() {
+        let s = Solution::from_str("(1,0)/(-3,0)/(0,0)/(6,-5)/").unwrap();
+        assert_eq!(s.slice_count(), 4);
+        assert_eq!(s.turn_value(), 1 + 3 + 6);
+        assert_eq!(s.twist_count(), 4 + 3);
+        assert_eq!(Solution { notation: vec![] }.slice_count(), 0);
+    }
+
+    #[test]
+    #[ignore = "needs generated tables"]
+    pub fn test_hybrid() {
+        for width in [1, 2] {
+            for _ in 0..10 {
+                let (square1, bar_solved) = Square1::scrambled();
+                let solution = solve_with_mode(square1.clone(), bar_solved, SolveMode::Hybrid(width)).unwrap();
+                assert_eq!(verify_solution(square1, bar_solved, &solution), Ok(()));
+            }
+        }
+    }
+
+    // Searches all turns, only fast enough close to solved
+    fn is_solvable(square1: &Square1, slices: u8) -> bool {
+        if slices == 0 {
+            square1.pieces[0] < 8 && square1.pieces[8] > 7 && {
+                let mut turned = square1.clone();
+                turned.turn_layers(&get_abf_turn(square1));
+                turned == Square1::solved()
+            }
+        } else {
+            square1.get_all_turns().into_iter().any(|turn| {
+                let mut adj = square1.clone();
+                adj.turn_layers(&turn);
+                adj.turn_slice().is_ok() && is_solvable(&adj, slices - 1)
+            })
+        }
+    }
+
+    #[test]
+    pub fn test_fast() {
+        let count = |square1: Square1, _| (0..).find(|&slices| is_solvable(&square1, slices)).unwrap();
+        for scramble in ["(1,0)/(3,0)/", "(0,-1)/(-3,0)/(1,0)/", "(1,0)/(-1,-1)/(0,-3)/"] {
+            let mut square1 = Square1::solved();
+            square1.apply(&Solution::from_str(scramble).unwrap()).unwrap();
+            let slices = count(square1.clone(), 0);
+            let mut path = get_greedy_path_raw(&square1, slices, slices, &count).unwrap();
+            path.reverse();
+            let solution = Solution { notation: path };
+            assert_eq!(solution.slice_count(), slices as usize);
+            square1.apply(&solution).unwrap();
+            assert_eq!(square1, Square1::solved());
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use core::array::from_fn;
+use std::fmt;
 use rand::Rng;
 
 use crate::solver::Solution;
@@ -19,25 +20,25 @@ const SQSQ_UNIQUE_TURNS_DA: [(usize, usize); 16] = [(0, 0), (4, 0), (2, 0), (6,
                                                     (1, 3), (1, 7), (7, 7), (3, 7)];
 
 
-const _SQSQ_ALL_TURNS_A: [(usize, usize); 32] = [(1, 0), (5, 0), (3, 0), (7, 0),
-                                                (1, 4), (5, 4), (3, 4), (7, 4),
-                                                (0, 1), (4, 1), (2, 1), (6, 1),
-                                                (0, 5), (4, 5), (2, 5), (6, 5),
-                                                (1, 2), (5, 2), (3, 2), (7, 2),
-                                                (1, 6), (5, 6), (3, 6), (7, 6),
-                                                (0, 3), (4, 3), (2, 3), (6, 3),
-                                                (0, 7), (4, 7), (2, 7), (6, 7)];
-
-const _SQSQ_ALL_TURNS_M: [(usize, usize); 32] = [(0, 0), (4, 0), (2, 0), (6, 0),
-                                                (0, 4), (4, 4), (2, 4), (6, 4),
-                                                (1, 1), (5, 1), (3, 1), (7, 1),
-                                                (1, 5), (5, 5), (3, 5), (7, 5),
-                                                (0, 2), (4, 2), (2, 2), (6, 2),
-                                                (0, 6), (4, 6), (2, 6), (6, 6),
-                                                (1, 3), (5, 3), (3, 3), (7, 3),
-                                                (1, 7), (5, 7), (3, 7), (7, 7)];
-
-#[derive(Clone, Debug)]
+const SQSQ_ALL_TURNS_A: [(usize, usize); 32] = [(1, 0), (5, 0), (3, 0), (7, 0),
+                                               (1, 4), (5, 4), (3, 4), (7, 4),
+                                               (0, 1), (4, 1), (2, 1), (6, 1),
+                                               (0, 5), (4, 5), (2, 5), (6, 5),
+                                               (1, 2), (5, 2), (3, 2), (7, 2),
+                                               (1, 6), (5, 6), (3, 6), (7, 6),
+                                               (0, 3), (4, 3), (2, 3), (6, 3),
+                                               (0, 7), (4, 7), (2, 7), (6, 7)];
+
+const SQSQ_ALL_TURNS_M: [(usize, usize); 32] = [(0, 0), (4, 0), (2, 0), (6, 0),
+                                               (0, 4), (4, 4), (2, 4), (6, 4),
+                                               (1, 1), (5, 1), (3, 1), (7, 1),
+                                               (1, 5), (5, 5), (3, 5), (7, 5),
+                                               (0, 2), (4, 2), (2, 2), (6, 2),
+                                               (0, 6), (4, 6), (2, 6), (6, 6),
+                                               (1, 3), (5, 3), (3, 3), (7, 3),
+                                               (1, 7), (5, 7), (3, 7), (7, 7)];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Square1 {
     pub(crate) pieces: [u8; 16],
 }
@@ -80,6 +81,11 @@ impl Square1 {
         Square1 {pieces: arr}
     }
 
+    // Cube shape with the 8 corners and 8 edges in the given order
+    pub fn from_permutations(corners: &[u8], edges: &[u8]) -> Square1 {
+        Square1::from_arr(std::array::from_fn(|i| if i % 2 == 0 {corners[i / 2]} else {edges[i / 2]}))
+    }
+
     pub fn from_num(mut num: u64) -> Square1 {
         let mut arr: [u8; 16] = [0; 16];
         for i in 0..16 {
@@ -89,25 +95,23 @@ impl Square1 {
         Square1 {pieces: arr}
     }
 
-    pub fn from_notation(sequence: Solution) -> Result<Self, ()> {
+    pub fn from_notation(sequence: Solution) -> Result<Self, SequenceError> {
         let mut square1 = Self::solved();
-        let mut first = true;
-        
-        for turn in sequence.notation {
-            if first {
-                first = false;
-            } else {
-                if square1.turn_slice().is_err() {
-                    println!("Sequence not sliceable");
-                    return Err(())
-                };
-            }
-            if square1.turn_layers_human_readable(&turn).is_err() {
-                println!("Sequence contains bad turns");
-                return Err(())
+        square1.apply(&sequence)?;
+        Ok(square1)
+    }
+
+    // Leaves the Square-1 untouched if any move fails
+    pub fn apply(&mut self, sequence: &Solution) -> Result<(), SequenceError> {
+        let mut square1 = self.clone();
+        for (index, &turn) in sequence.notation.iter().enumerate() {
+            if index > 0 {
+                square1.apply_slice().map_err(|error| SequenceError { index: index - 1, error })?;
             }
+            square1.apply_turn(turn).map_err(|error| SequenceError { index, error })?;
         }
-        Ok(square1)
+        *self = square1;
+        Ok(())
     }
 
     pub fn get_num(&self) -> u64 {
@@ -119,7 +123,7 @@ impl Square1 {
         num
     }
 
-    fn get_angle(&self, index: usize) -> u8 {
+    pub(crate) fn get_angle(&self, index: usize) -> u8 {
         2 - (self.pieces[index] & 1)
     }
 
@@ -146,7 +150,7 @@ impl Square1 {
         Ok(())
     }
 
-    fn get_divide(&self) -> usize {
+    pub(crate) fn get_divide(&self) -> usize {
         let mut angle: u8 = 0;
         let mut divide: usize = 0;
         while angle < 12 {
@@ -337,11 +341,11 @@ impl Square1 {
         }
     }
 
-    pub(crate) fn _get_all_turns_sqsq(&self) -> [(usize, usize); 32] {
+    pub fn get_all_turns_sqsq(&self) -> [(usize, usize); 32] {
         if self.pieces[0] & 1 != self.pieces[15] & 1 {
-            _SQSQ_ALL_TURNS_A
+            SQSQ_ALL_TURNS_A
         } else {
-            _SQSQ_ALL_TURNS_M
+            SQSQ_ALL_TURNS_M
         }
     }
 
@@ -376,17 +380,33 @@ impl Square1 {
     }
 
     pub fn is_valid(&self) -> bool {
-        let mut set = self.pieces.to_vec();
-        set.sort();
-        set.dedup();
-        self.pieces.len() == 16 && set.len() == 16
+        self.validate().is_ok()
     }
 
-    fn turn_layers_human_readable(&mut self, turn: &(i8, i8)) -> Result<(), ()> {
+    // Every arrangement of layers with 12 units each can be reached, so only the pieces and angles get checked
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        let mut seen = [false; 16];
+        for &piece in &self.pieces {
+            if piece > 15 {
+                return Err(ValidationError::UnknownPiece(piece))
+            } else if seen[piece as usize] {
+                return Err(ValidationError::DuplicatePiece(piece))
+            }
+            seen[piece as usize] = true;
+        }
+        let up_angle: u8 = (0..self.get_divide()).map(|i| self.get_angle(i)).sum();
+        if up_angle != 12 {
+            Err(ValidationError::LayerAngle(up_angle))
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn apply_turn(&mut self, turn: (i8, i8)) -> Result<(), MoveError> {
         let (mut up, mut down) = (0, 0);
         let (mut up_pos, mut down_pos) = (false, false);
-        let up_angle = if turn.0 <= 0 {- turn.0} else {12 - turn.0} as usize;
-        let down_angle = if turn.1 < 0 {turn.1 + 12} else {turn.1} as usize + 12;
+        let up_angle = ((12 - turn.0.rem_euclid(12)) % 12) as usize;
+        let down_angle = turn.1.rem_euclid(12) as usize + 12;
         let mut angle = 0;
         for i in 0..16 {
             if angle < 12 {
@@ -395,24 +415,122 @@ impl Square1 {
                 } else if angle == up_angle {
                     up_pos = true;
                 }
-            } else {
-                if angle < down_angle {
-                    down += 1;
-                } else if angle == down_angle {
-                    down_pos = true;
-                }
-            }
-            match self.pieces[i] % 2 {
-                0 => angle += 2,
-                1 => angle += 1,
-                _ => {}
+            } else if angle < down_angle {
+                down += 1;
+            } else if angle == down_angle {
+                down_pos = true;
             }
+            angle += self.get_angle(i) as usize;
         }
         if up_pos && down_pos {
             self.turn_layers(&(up, down));
             Ok(())
         } else {
-            Err(())
+            Err(MoveError::BadTurn { turn, up: !up_pos, down: !down_pos })
         }
     }
+
+    pub fn apply_slice(&mut self) -> Result<(), MoveError> {
+        self.turn_slice().map_err(|_| MoveError::MisalignedSlice)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MoveError {
+    // The turn doesn't land on a piece boundary in the marked layers
+    BadTurn { turn: (i8, i8), up: bool, down: bool },
+    MisalignedSlice
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MoveError::BadTurn { turn, up, down } => {
+                let layers = match (up, down) {
+                    (true, true) => "up and down layer",
+                    (true, false) => "up layer",
+                    _ => "down layer"
+                };
+                write!(f, "turn ({},{}) doesn't land on a piece boundary in the {}", turn.0, turn.1, layers)
+            }
+            MoveError::MisalignedSlice => write!(f, "slice is blocked by a piece lying across it")
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ValidationError {
+    UnknownPiece(u8),
+    DuplicatePiece(u8),
+    // A piece lies across the divide, so the up layer has this angle instead of 12
+    LayerAngle(u8)
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationError::UnknownPiece(piece) => write!(f, "piece {} doesn't exist", piece),
+            ValidationError::DuplicatePiece(piece) => write!(f, "piece {} appears more than once", piece),
+            ValidationError::LayerAngle(angle) => write!(f, "up layer spans {} instead of 12 units", angle)
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SequenceError {
+    // Index of the failing turn, or of the turn before the failing slice
+    pub index: usize,
+    pub error: MoveError
+}
+
+impl fmt::Display for SequenceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.error {
+            MoveError::BadTurn { .. } => write!(f, "move {}: {}", self.index + 1, self.error),
+            MoveError::MisalignedSlice => write!(f, "slice after move {}: {}", self.index + 1, self.error)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use crate::{solver::Solution, square1::{MoveError, SequenceError, Square1, ValidationError}};
+
+    #[test]
+    pub fn test_apply() {
+        let solution = Solution::from_str("(0,-2)/(2,0)/(-1,4)/(-3,0)/(0,-1)/(-2,4)/(0,3)/(-1,2)/(-3,-3)/(-5,-2)/(-4,-3)").unwrap();
+        let mut square1 = Square1::from_notation(solution.inverse()).unwrap();
+        square1.apply(&solution).unwrap();
+        assert_eq!(square1, Square1::solved());
+        // Turns are taken modulo 12, down to the smallest i8
+        let mut turned = Square1::solved();
+        turned.apply_turn((-128, 0)).unwrap();
+        let mut expected = Square1::solved();
+        expected.apply_turn((4, 0)).unwrap();
+        assert_eq!(turned, expected);
+    }
+
+    #[test]
+    pub fn test_apply_errors() {
+        let mut square1 = Square1::solved();
+        assert_eq!(square1.apply_turn((2, 0)), Err(MoveError::BadTurn { turn: (2, 0), up: true, down: false }));
+        assert_eq!(square1.apply_turn((0, 1)), Err(MoveError::BadTurn { turn: (0, 1), up: false, down: true }));
+
+        let start = Square1::from_arr([0, 1, 2, 3, 4, 6, 8, 10, 5, 12, 7, 14, 9, 11, 13, 15]);
+        let mut square1 = start.clone();
+        let error = square1.apply(&Solution::from_str("(-2,0)/(0,0)/(-1,0)/").unwrap());
+        assert_eq!(error, Err(SequenceError { index: 2, error: MoveError::MisalignedSlice }));
+        assert_eq!(square1, start);
+    }
+    #[test]
+    pub fn test_validate() {
+        assert_eq!(Square1::solved().validate(), Ok(()));
+        assert_eq!(Square1::scrambled().0.validate(), Ok(()));
+        assert_eq!(Square1::from_arr([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 16]).validate(), Err(ValidationError::UnknownPiece(16)));
+        assert_eq!(Square1::from_arr([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 14]).validate(), Err(ValidationError::DuplicatePiece(14)));
+        assert_eq!(Square1::from_arr([0, 1, 2, 3, 4, 5, 6, 8, 7, 9, 10, 11, 12, 13, 14, 15]).validate(), Err(ValidationError::LayerAngle(13)));
+        assert!(!Square1::from_arr([0, 1, 2, 3, 4, 5, 6, 8, 7, 9, 10, 11, 12, 13, 14, 15]).is_valid());
+    }
 }
\ No newline at end of file
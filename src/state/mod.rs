@@ -3,16 +3,48 @@ mod symmetry;
 pub mod statecs;
 pub mod statesqsq;
 pub mod stateall;
+pub mod statevandenbergh;
+pub mod statelin;
+pub mod statesubset;
+pub mod statepieces;
+pub mod stateorientation;
+pub mod movetable;
 
 use crate::square1::Square1;
 
 pub trait State {
+    // Settings of a State configured at runtime, () for all others
+    type Config: Sync;
     const NAME: &str;
     const SIZE: usize;
     const MAX_SLICES: u8;
-    fn new(sq1: Square1) -> Self;
+    fn new_with(sq1: Square1, config: &Self::Config) -> Self;
     fn get_index(&self) -> usize;
     fn get_symmetric_indecies(&mut self) -> Vec<usize>;
     fn get_square1_num(&self) -> u64;
     fn gen_next_positions(sq1num: u64) -> Vec<u64>;
-}
\ No newline at end of file
+
+    fn new(sq1: Square1) -> Self where Self: Sized, Self::Config: Default {
+        Self::new_with(sq1, &Self::Config::default())
+    }
+
+    // Number of indecies in the table
+    fn size(_config: &Self::Config) -> usize {
+        Self::SIZE
+    }
+
+    // Name of the table file
+    fn name(_config: &Self::Config) -> String {
+        Self::NAME.to_string()
+    }
+
+    // Number of a Position for gen_next_positions, the Square-1 number unless the State has its own coordinates
+    fn get_position_num(sq1: &Square1) -> u64 {
+        sq1.get_num()
+    }
+
+    // State of a number from gen_next_positions
+    fn from_position_num(num: u64, config: &Self::Config) -> Self where Self: Sized {
+        Self::new_with(Square1::from_num(num), config)
+    }
+}
@@ -0,0 +1,180 @@
+use std::sync::LazyLock;
+
+use crate::square1::Square1;
+
+use super::statesubset::binomial;
+
+static MOVE_TABLE: LazyLock<MoveTable> = LazyLock::new(MoveTable::new);
+
+// Square square Positions are kept with a corner at the start of both layers, which takes at most
+// one free layer turn. The corner and the edge slots are then counted from the start of the up layer.
+// A move is one of the 32 turns get_all_turns_sqsq allows there, followed by the slice and
+// the layer turns that bring a corner back to the start of both layers.
+
+pub const CO_SIZE: usize = 70;
+
+pub const PERM_SIZE: usize = 40_320;
+
+pub const MOVES: usize = 32;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SqSqCoord {
+    // Slots of the up colored corners
+    pub co: usize,
+    pub cp: usize,
+    pub ep: usize
+}
+
+// Next coordinate for each coordinate and move, at coordinate * MOVES + move
+struct MoveTable {
+    co: Vec<u8>,
+    cp: Vec<u16>,
+    ep: Vec<u16>,
+    // Corner orientation of each corner permutation
+    orientation: Vec<u8>,
+    unique: Vec<usize>
+}
+
+impl SqSqCoord {
+    pub fn new(sq1: &Square1) -> Self {
+        let mut sq1 = sq1.clone();
+        sq1.turn_layers(&((sq1.pieces[0] & 1) as usize, (sq1.pieces[8] & 1) as usize));
+        let corners: Vec<u8> = sq1.pieces.iter().step_by(2).map(|&piece| piece / 2).collect();
+        let edges: Vec<u8> = sq1.pieces.iter().skip(1).step_by(2).map(|&piece| piece / 2).collect();
+        Self {co: get_orientation(&corners), cp: get_rank(&corners), ep: get_rank(&edges)}
+    }
+
+    pub fn to_square1(&self) -> Square1 {
+        let corners = from_rank(self.cp);
+        let edges = from_rank(self.ep);
+        Square1::from_arr(std::array::from_fn(|i| if i % 2 == 0 {corners[i / 2] * 2} else {edges[i / 2] * 2 + 1}))
+    }
+
+    // The move is an index into get_all_turns_sqsq
+    pub fn apply(&self, mv: usize) -> Self {
+        let table = &*MOVE_TABLE;
+        Self {
+            co: table.co[self.co * MOVES + mv] as usize,
+            cp: table.cp[self.cp * MOVES + mv] as usize,
+            ep: table.ep[self.ep * MOVES + mv] as usize
+        }
+    }
+
+    // The moves get_unique_turns_sqsq keeps
+    pub fn get_unique_moves() -> &'static [usize] {
+        &MOVE_TABLE.unique
+    }
+
+    // The orientation follows from the corners
+    pub fn get_num(&self) -> u64 {
+        (self.cp * PERM_SIZE + self.ep) as u64
+    }
+
+    pub fn from_num(num: u64) -> Self {
+        let cp = num as usize / PERM_SIZE;
+        Self {co: MOVE_TABLE.orientation[cp] as usize, cp, ep: num as usize % PERM_SIZE}
+    }
+}
+
+impl MoveTable {
+    fn new() -> Self {
+        let turns = Square1::solved().get_all_turns_sqsq();
+        // Where the corners and edges of each slot come from after the move
+        let perms: Vec<([usize; 8], [usize; 8])> = turns.iter().map(|turn| {
+            let mut sq1 = Square1::solved();
+            sq1.turn_layers(turn);
+            sq1.turn_slice().expect("All Turns is wrong");
+            let moved = SqSqCoord::new(&sq1).to_square1();
+            (std::array::from_fn(|i| moved.pieces[i * 2] as usize / 2), std::array::from_fn(|i| moved.pieces[i * 2 + 1] as usize / 2))
+        }).collect();
+
+        let mut co = vec![0; CO_SIZE * MOVES];
+        let mut cp = vec![0; PERM_SIZE * MOVES];
+        let mut ep = vec![0; PERM_SIZE * MOVES];
+        let mut orientations = vec![0; PERM_SIZE];
+        for rank in 0..PERM_SIZE {
+            let labels = from_rank(rank);
+            let orientation = get_orientation(&labels);
+            orientations[rank] = orientation as u8;
+            for (mv, (corner_perm, edge_perm)) in perms.iter().enumerate() {
+                let corners: Vec<u8> = corner_perm.iter().map(|&slot| labels[slot]).collect();
+                let edges: Vec<u8> = edge_perm.iter().map(|&slot| labels[slot]).collect();
+                co[orientation * MOVES + mv] = get_orientation(&corners) as u8;
+                cp[rank * MOVES + mv] = get_rank(&corners) as u16;
+                ep[rank * MOVES + mv] = get_rank(&edges) as u16;
+            }
+        }
+
+        let unique = Square1::solved().get_unique_turns_sqsq().iter().map(|turn| {
+            turns.iter().position(|other| other == turn).expect("Unique Turn is missing in All Turns")
+        }).collect();
+        Self {co, cp, ep, orientation: orientations, unique}
+    }
+}
+
+// Combination of the slots with up colored corners
+fn get_orientation(corners: &[u8]) -> usize {
+    corners.iter().enumerate().filter(|&(_, &corner)| corner < 4).enumerate()
+        .map(|(i, (slot, _))| binomial(slot, i + 1)).sum()
+}
+
+// Counts the earlier labels that are higher, the same way StateSqSq does
+pub(super) fn get_rank(labels: &[u8]) -> usize {
+    let mut factor = 1;
+    let mut rank = 0;
+    for i in 1..labels.len() {
+        factor *= i;
+        rank += labels[..i].iter().filter(|&&label| label > labels[i]).count() * factor;
+    }
+    rank
+}
+
+fn from_rank(mut rank: usize) -> [u8; 8] {
+    let mut higher = [0; 8];
+    for (i, count) in higher.iter_mut().enumerate().skip(1) {
+        *count = rank % (i + 1);
+        rank /= i + 1;
+    }
+    let mut left: Vec<u8> = (0..8).collect();
+    let mut labels = [0; 8];
+    for i in (0..8).rev() {
+        labels[i] = left.remove(left.len() - 1 - higher[i]);
+    }
+    labels
+}
+
+#[cfg(test)]
+mod test {
+    use rand::Rng;
+
+    use crate::{square1::Square1, state::{statesqsq::StateSqSq, State}};
+
+    use super::{from_rank, get_rank, SqSqCoord, MOVES};
+
+    #[test]
+    pub fn test_move_table() {
+        assert_eq!(get_rank(&from_rank(12_345)), 12_345);
+        let solved = Square1::solved();
+        assert_eq!(SqSqCoord::new(&solved).to_square1(), solved);
+
+        let mut rng = rand::rng();
+        let mut sq1 = solved;
+        for _ in 0..200 {
+            let turns = sq1.get_all_turns_sqsq();
+            sq1.turn_layers(&turns[rng.random_range(0..turns.len())]);
+            sq1.turn_slice().unwrap();
+            let coord = SqSqCoord::new(&sq1);
+            assert_eq!(SqSqCoord::from_num(coord.get_num()), coord);
+            assert_eq!(StateSqSq::from_coord(coord).get_index(), StateSqSq::new(sq1.clone()).get_index());
+
+            // Every move matches turning and slicing the Square-1
+            let base = coord.to_square1();
+            for (mv, turn) in base.get_all_turns_sqsq().iter().enumerate().take(MOVES) {
+                let mut adj = base.clone();
+                adj.turn_layers(turn);
+                adj.turn_slice().unwrap();
+                assert_eq!(coord.apply(mv), SqSqCoord::new(&adj));
+            }
+        }
+    }
+}
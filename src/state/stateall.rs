@@ -17,13 +17,15 @@ pub struct StateAll {
 }
 
 impl State for StateAll {
+    type Config = ();
+
     const NAME: &str = "all";
 
     const SIZE: usize = 3_302_208_000;
 
     const MAX_SLICES: u8 = 12;
 
-    fn new(sq1: Square1) -> Self {
+    fn new_with(sq1: Square1, _: &()) -> Self {
         let mut state: Self = Self {sq1, cubeshape: 0, co: 0, cp_black: 0, cp_white: 0, ep: 0, index: 0, up_case: 0, down_case: 0, up_re: 0, down_re: 0};
         state.calc_index();
         state
@@ -2,6 +2,9 @@ use crate::square1::Square1;
 
 use super::State;
 
+// The index is parity * CUBESHAPES + cube shape class, only the first SIZE - CUBESHAPES classes have an odd parity
+pub const CUBESHAPES: usize = 65;
+
 pub struct StateCS {
     sq1: Square1,
     cubeshape: usize,
@@ -10,13 +13,15 @@ pub struct StateCS {
 }
 
 impl State for StateCS {
+    type Config = ();
+
     const NAME: &str = "cs";
 
     const SIZE: usize = 113;
 
     const MAX_SLICES: u8 = 7;
 
-    fn new(sq1: Square1) -> Self {
+    fn new_with(sq1: Square1, _: &()) -> Self {
         let mut state: Self = Self {sq1, cubeshape: 0, parity: 0, index: 0};
         state.calc_index();
         state
@@ -51,6 +56,15 @@ impl State for StateCS {
 }
 
 impl StateCS {
+    pub fn get_cubeshape(&self) -> usize {
+        self.cubeshape
+    }
+
+    // Shape classes without a parity always return 0
+    pub fn get_parity(&self) -> usize {
+        self.parity
+    }
+
     fn calc_index(&mut self) {
         let mut up_shape = self.get_shape(true);
         let mut down_shape = self.get_shape(false);
@@ -122,7 +136,7 @@ impl StateCS {
             },
             false => {}
         }
-        self.index = self.parity * 65 + self.cubeshape;
+        self.index = self.parity * CUBESHAPES + self.cubeshape;
     }
 
     fn get_shape(&self, for_up: bool) -> Vec<usize> {
@@ -0,0 +1,108 @@
+use crate::square1::Square1;
+
+use super::State;
+use super::statevandenbergh::{gen_next_positions_sqsq, get_layers, get_permutation};
+
+// The Lin steps only look at positions in cube shape. The first block is the down layer
+// without the edge 15, that edge is the last slot and gets solved with the up edges.
+// Untracked pieces get the highest label, so tracked pieces are found by their label.
+// MAX_SLICES is the deepest count the generation finds.
+
+// Where the down corners and the down edges 9, 11 and 13 are
+pub struct StateFB {
+    sq1: Square1,
+    index: usize
+}
+
+// Where the corners are and where the block edges are
+pub struct StateLinCP {
+    sq1: Square1,
+    index: usize
+}
+
+impl State for StateFB {
+    type Config = ();
+
+    const NAME: &str = "fb";
+
+    const SIZE: usize = 2_097_152;
+
+    const MAX_SLICES: u8 = 7;
+
+    fn new_with(sq1: Square1, _: &()) -> Self {
+        let [up, down] = get_layers(&sq1, |piece| match piece {
+            8 | 10 | 12 | 14 => piece / 2 - 4,
+            9 | 11 | 13 => (piece - 9) / 2,
+            _ => if piece & 1 == 0 {4} else {3}
+        });
+        let (corners, edges) = split_pieces(&up, &down);
+        let index = (get_slots(&corners, 4) << 9) + get_slots(&edges, 3);
+        Self {sq1, index}
+    }
+
+    fn get_index(&self) -> usize {
+        self.index
+    }
+
+    fn get_symmetric_indecies(&mut self) -> Vec<usize> {
+        vec![]
+    }
+
+    fn get_square1_num(&self) -> u64 {
+        self.sq1.get_num()
+    }
+
+    fn gen_next_positions(sq1num: u64) -> Vec<u64> {
+        gen_next_positions_sqsq(sq1num)
+    }
+}
+
+impl State for StateLinCP {
+    type Config = ();
+
+    const NAME: &str = "lcp";
+
+    const SIZE: usize = 20_643_840;
+
+    const MAX_SLICES: u8 = 8;
+
+    fn new_with(sq1: Square1, _: &()) -> Self {
+        let [up, down] = get_layers(&sq1, |piece| match piece {
+            9 | 11 | 13 => (piece - 9) / 2,
+            _ => if piece & 1 == 0 {piece / 2} else {3}
+        });
+        let (corners, edges) = split_pieces(&up, &down);
+        let index = (get_permutation(&corners) << 9) + get_slots(&edges, 3);
+        Self {sq1, index}
+    }
+
+    fn get_index(&self) -> usize {
+        self.index
+    }
+
+    fn get_symmetric_indecies(&mut self) -> Vec<usize> {
+        vec![]
+    }
+
+    fn get_square1_num(&self) -> u64 {
+        self.sq1.get_num()
+    }
+
+    fn gen_next_positions(sq1num: u64) -> Vec<u64> {
+        gen_next_positions_sqsq(sq1num)
+    }
+}
+
+// Both layers start at a corner, so corners and edges alternate
+fn split_pieces(up: &[u8], down: &[u8]) -> (Vec<u8>, Vec<u8>) {
+    let corners = up.iter().chain(down).step_by(2).copied().collect();
+    let edges = up.iter().chain(down).skip(1).step_by(2).copied().collect();
+    (corners, edges)
+}
+
+// Slots of the tracked labels 0..count, 3 bits each
+fn get_slots(labels: &[u8], count: u8) -> usize {
+    (0..count).fold(0, |slots, label| {
+        (slots << 3) + labels.iter().position(|&other| other == label).expect("Tracked piece is missing")
+    })
+}
@@ -0,0 +1,49 @@
+use crate::square1::Square1;
+
+use super::State;
+use super::statepieces::{gen_next_positions, get_shape_labels};
+use super::statesubset::binomial;
+
+// Shape and which pieces have the up color, solved is cube shape with the colors separated
+pub struct StateOrientation {
+    sq1: Square1,
+    index: usize
+}
+
+impl State for StateOrientation {
+    type Config = ();
+
+    const NAME: &str = "orientation";
+
+    const SIZE: usize = 833_000;
+
+    const MAX_SLICES: u8 = 12;
+
+    fn new_with(sq1: Square1, _: &()) -> Self {
+        let (shape, labels) = get_shape_labels(&sq1, |piece| ((piece >> 3) << 1) + (piece & 1));
+        // Ranks the 4 up colored slots among the 8 corner and the 8 edge slots
+        let index = [0, 1].into_iter().fold(shape, |index, kind| {
+            let slots: Vec<u8> = labels.iter().filter(|&&label| label & 1 == kind).copied().collect();
+            let rank: usize = slots.iter().enumerate().filter(|&(_, &label)| label < 2).enumerate()
+                .map(|(i, (slot, _))| binomial(slot, i + 1)).sum();
+            index * 70 + rank
+        });
+        Self {sq1, index}
+    }
+
+    fn get_index(&self) -> usize {
+        self.index
+    }
+
+    fn get_symmetric_indecies(&mut self) -> Vec<usize> {
+        vec![]
+    }
+
+    fn get_square1_num(&self) -> u64 {
+        self.sq1.get_num()
+    }
+
+    fn gen_next_positions(sq1num: u64) -> Vec<u64> {
+        gen_next_positions(sq1num)
+    }
+}
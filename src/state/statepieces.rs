@@ -0,0 +1,147 @@
+use std::{collections::BTreeSet, sync::LazyLock};
+
+use crate::square1::Square1;
+
+use super::State;
+use super::statevandenbergh::get_permutation;
+
+// Shapes as the corner pattern of both layers, each turned to its smallest pattern
+static SHAPES: LazyLock<Vec<[Vec<bool>; 2]>> = LazyLock::new(get_shapes);
+
+// The States only use layer turns as symmetry, anything else would move the ignored pieces
+// into the index. Each layer is turned to its smallest corner pattern, ties are broken by the
+// tracked pieces. The index is the shape and the order of the tracked pieces.
+// Shapes are told apart by their index in SHAPES, there are 170 of them.
+
+// Shape and corners, ignoring the edges
+pub struct StateCorners {
+    sq1: Square1,
+    index: usize
+}
+
+// Shape and edges, ignoring the corners
+pub struct StateEdges {
+    sq1: Square1,
+    index: usize
+}
+
+impl State for StateCorners {
+    type Config = ();
+
+    const NAME: &str = "corners";
+
+    const SIZE: usize = 6_854_400;
+
+    const MAX_SLICES: u8 = 12;
+
+    fn new_with(sq1: Square1, _: &()) -> Self {
+        let index = get_index(&sq1, 0);
+        Self {sq1, index}
+    }
+
+    fn get_index(&self) -> usize {
+        self.index
+    }
+
+    fn get_symmetric_indecies(&mut self) -> Vec<usize> {
+        vec![]
+    }
+
+    fn get_square1_num(&self) -> u64 {
+        self.sq1.get_num()
+    }
+
+    fn gen_next_positions(sq1num: u64) -> Vec<u64> {
+        gen_next_positions(sq1num)
+    }
+}
+
+impl State for StateEdges {
+    type Config = ();
+
+    const NAME: &str = "edges";
+
+    const SIZE: usize = 6_854_400;
+
+    const MAX_SLICES: u8 = 12;
+
+    fn new_with(sq1: Square1, _: &()) -> Self {
+        let index = get_index(&sq1, 1);
+        Self {sq1, index}
+    }
+
+    fn get_index(&self) -> usize {
+        self.index
+    }
+
+    fn get_symmetric_indecies(&mut self) -> Vec<usize> {
+        vec![]
+    }
+
+    fn get_square1_num(&self) -> u64 {
+        self.sq1.get_num()
+    }
+
+    fn gen_next_positions(sq1num: u64) -> Vec<u64> {
+        gen_next_positions(sq1num)
+    }
+}
+
+// Index of the shape and the order of the corners (0) or edges (1)
+fn get_index(sq1: &Square1, kind: u8) -> usize {
+    let (shape, labels) = get_shape_labels(sq1, |piece| if piece & 1 == kind {piece / 2} else {8});
+    let tracked: Vec<u8> = labels.into_iter().filter(|&label| label < 8).collect();
+    shape * 40_320 + get_permutation(&tracked)
+}
+
+// Index of the shape and the labels of both layers, each layer turned to its smallest pattern and labels
+pub(super) fn get_shape_labels(sq1: &Square1, label: impl Fn(u8) -> u8) -> (usize, Vec<u8>) {
+    let divide = sq1.get_divide();
+    let [up, down] = [&sq1.pieces[..divide], &sq1.pieces[divide..]].map(|layer| {
+        (0..layer.len()).map(|turn| {
+            let turned: Vec<u8> = layer[turn..].iter().chain(&layer[..turn]).copied().collect();
+            let pattern: Vec<bool> = turned.iter().map(|&piece| piece & 1 == 1).collect();
+            let labels: Vec<u8> = turned.into_iter().map(&label).collect();
+            (pattern, labels)
+        }).min().unwrap()
+    });
+    let shape = SHAPES.binary_search(&[up.0, down.0]).expect("Shape is missing");
+    (shape, up.1.into_iter().chain(down.1).collect())
+}
+
+// Every pair of layers with 8 corners and 8 edges, an edge counts 1 and a corner 2 of the 12 units
+fn get_shapes() -> Vec<[Vec<bool>; 2]> {
+    let mut layers = BTreeSet::new();
+    let mut open = vec![vec![]];
+    while let Some(layer) = open.pop() {
+        let units: usize = layer.iter().map(|&edge| if edge {1} else {2}).sum();
+        if units == 12 {
+            let smallest = (0..layer.len()).map(|turn| [&layer[turn..], &layer[..turn]].concat()).min().unwrap();
+            layers.insert(smallest);
+        } else if units < 12 {
+            for edge in [false, true] {
+                open.push([&layer[..], &[edge]].concat());
+            }
+        }
+    }
+    let edges = |layer: &Vec<bool>| layer.iter().filter(|&&edge| edge).count();
+    let mut shapes = vec![];
+    for up in &layers {
+        for down in &layers {
+            if edges(up) + edges(down) == 8 && up.len() + down.len() == 16 {
+                shapes.push([up.clone(), down.clone()]);
+            }
+        }
+    }
+    shapes
+}
+
+pub(super) fn gen_next_positions(sq1num: u64) -> Vec<u64> {
+    let base: Square1 = Square1::from_num(sq1num);
+    base.get_all_turns().into_iter().map(|turn: (usize, usize)| {
+        let mut adj: Square1 = base.clone();
+        adj.turn_layers(&turn);
+        adj.turn_slice().expect("All Turns is wrong");
+        adj.get_num()
+    }).collect()
+}
@@ -1,31 +1,67 @@
 
+use std::sync::LazyLock;
+
 use crate::square1::Square1;
 
 use super::State;
+use super::movetable::{get_rank, SqSqCoord, PERM_SIZE};
 use super::symmetry::Symmetry;
 
+// What new() does with the corners of each corner permutation, from a corner at the start of both layers
+static CORNER_TABLE: LazyLock<Vec<Corners>> = LazyLock::new(|| (0..PERM_SIZE).map(|cp| {
+    let state = StateSqSq::new(SqSqCoord {co: 0, cp, ep: 0}.to_square1());
+    Corners {co: state.co, cp_black: state.cp_black, cp_white: state.cp_white, flipped: state.flipped, rotation: state.rotation, cycle: state.cycle}
+}).collect());
+
+// The edges after each step of new(), for every edge permutation from a corner at the start of both layers
+static EDGE_TABLE: LazyLock<EdgeTable> = LazyLock::new(EdgeTable::new);
+
 pub struct StateSqSq {
     sq1: Square1,
+    // Coordinates the Square-1 is only made from when it's needed
+    coord: Option<SqSqCoord>,
     co: usize,
     cp_black: usize,
     cp_white: usize,
     ep: usize,
     index: usize,
     up_alignment: usize,
-    down_alignment: usize
+    down_alignment: usize,
+    flipped: bool,
+    rotation: (usize, usize),
+    cycle: (u8, u8)
+}
+
+struct EdgeTable {
+    flip: Vec<u16>,
+    up_rot: [Vec<u16>; 4],
+    down_rot: [Vec<u16>; 4],
+    black_cycle: [Vec<u16>; 4],
+    white_cycle: [Vec<u16>; 4]
+}
+
+struct Corners {
+    co: usize,
+    cp_black: usize,
+    cp_white: usize,
+    flipped: bool,
+    rotation: (usize, usize),
+    cycle: (u8, u8)
 }
 
 impl State for StateSqSq {
+    type Config = ();
+
     const NAME: &str = "sqsq";
 
     const SIZE: usize = 3_628_800;
 
     const MAX_SLICES: u8 = 9;
 
-    fn new(sq1: Square1) -> Self {
+    fn new_with(sq1: Square1, _: &()) -> Self {
         let up_alignment: usize = (sq1.pieces[0] & 1) as usize;
         let down_alignment: usize = (sq1.pieces[8] & 1) as usize;
-        let mut state: Self = Self {sq1, co: 0, cp_black: 0, cp_white: 0, ep: 0, index: 0, up_alignment, down_alignment};
+        let mut state: Self = Self {sq1, coord: None, co: 0, cp_black: 0, cp_white: 0, ep: 0, index: 0, up_alignment, down_alignment, flipped: false, rotation: (0, 0), cycle: (0, 0)};
         state.calc_index();
         state
     }
@@ -35,6 +71,9 @@ impl State for StateSqSq {
     }
 
     fn get_symmetric_indecies(&mut self) -> Vec<usize> {
+        if let Some(coord) = self.coord.take() {
+            *self = Self::new(coord.to_square1());
+        }
         let sq1 = self.sq1.clone();
         get_symmetric_actions(self.co).iter().map(|action| {
             if action.flip_l {
@@ -58,21 +97,45 @@ impl State for StateSqSq {
     }
 
     fn get_square1_num(&self) -> u64 {
-        self.sq1.get_num()
+        self.coord.map_or_else(|| self.sq1.get_num(), |coord| coord.to_square1().get_num())
     }
 
-    fn gen_next_positions(sq1num: u64) -> Vec<u64> {
-        let base: Square1 = Square1::from_num(sq1num);
-        base.get_unique_turns_sqsq().map(|turn: (usize, usize)| {
-            let mut adj: Square1 = base.clone();
-            adj.turn_layers(&turn);
-            adj.turn_slice().expect("Unique Turns is wrong");
-            adj.get_num()
-        }).to_vec()
+    // Generation runs on the packed coordinates, so the next Positions are looked up in the move tables
+    fn gen_next_positions(num: u64) -> Vec<u64> {
+        let base = SqSqCoord::from_num(num);
+        SqSqCoord::get_unique_moves().iter().map(|&mv| base.apply(mv).get_num()).collect()
+    }
+
+    fn get_position_num(sq1: &Square1) -> u64 {
+        SqSqCoord::new(sq1).get_num()
+    }
+
+    fn from_position_num(num: u64, _: &()) -> Self {
+        Self::from_coord(SqSqCoord::from_num(num))
     }
 }
 
 impl StateSqSq {
+    // Looks up the corners and edges instead of turning them into place
+    pub fn from_coord(coord: SqSqCoord) -> Self {
+        let corners = &CORNER_TABLE[coord.cp];
+        let edges = &*EDGE_TABLE;
+        let mut ep = coord.ep;
+        if corners.flipped {
+            ep = edges.flip[ep] as usize;
+        }
+        ep = edges.up_rot[corners.rotation.0][ep] as usize;
+        ep = edges.down_rot[corners.rotation.1][ep] as usize;
+        ep = edges.black_cycle[corners.cycle.0 as usize][ep] as usize;
+        ep = edges.white_cycle[corners.cycle.1 as usize][ep] as usize;
+        let mut state = Self {
+            sq1: Square1::solved(), coord: Some(coord), co: corners.co, cp_black: corners.cp_black, cp_white: corners.cp_white, ep: ep / 2, index: 0,
+            up_alignment: 0, down_alignment: 0, flipped: corners.flipped, rotation: corners.rotation, cycle: corners.cycle
+        };
+        state.combine_to_index();
+        state
+    }
+
     fn calc_index(&mut self) {
         self.calc_orientation();
         self.calc_permutation();
@@ -99,6 +162,7 @@ impl StateSqSq {
                 self.co = 1;
                 if black {
                     self.flip_layers();
+                    self.flipped = true;
                 }
             }
             2 => {
@@ -113,18 +177,21 @@ impl StateSqSq {
                 }
                 if self.co == 3 && up_is_opp {
                     self.flip_layers();
+                    self.flipped = true;
                 }
             }
             3 => {
                 self.co = 1;
                 if !black {
                     self.flip_layers();
+                    self.flipped = true;
                 }
             }
             _ => {
                 self.co = 0;
                 if !black {
                     self.flip_layers();
+                    self.flipped = true;
                 }
             }
         }
@@ -157,6 +224,7 @@ impl StateSqSq {
             _ => {}
         }
 
+        self.rotation = (up_rot, down_rot);
         self.rotate_layers(up_rot, down_rot);
     }
 
@@ -177,7 +245,8 @@ impl StateSqSq {
             }
             index += 1;
         }
-        self.sq1.cycle_colors(&(black_offset.unwrap(), white_offset.unwrap()));
+        self.cycle = (black_offset.unwrap(), white_offset.unwrap());
+        self.sq1.cycle_colors(&self.cycle);
 
         let mut blacks: Vec<u8> = vec![];
         let mut whites: Vec<u8> = vec![];
@@ -207,19 +276,12 @@ impl StateSqSq {
             self.cp_black += blacks_higher * factor;
             self.cp_white += whites_higher * factor;
         }
-        factor = 1;
-        for i in 1..8 {
-            factor *= i;
-            let mut edges_higher: usize = 0;
-            let edgei: u8 = self.get_edge(i);
-            for j in 0..i {
-                if self.get_edge(j) > edgei {
-                    edges_higher += 1;
-                }
-            }
-            self.ep += edges_higher * factor;
-        }
-        self.ep /= 2;
+        self.calc_edge_permutation();
+    }
+
+    fn calc_edge_permutation(&mut self) {
+        let edges: Vec<u8> = (0..8).map(|i| self.get_edge(i)).collect();
+        self.ep = get_rank(&edges) / 2;
     }
 
     fn get_corner(&self, corner_index: usize) -> u8 {
@@ -262,6 +324,25 @@ impl StateSqSq {
     
 }
 
+impl EdgeTable {
+    fn new() -> Self {
+        let step = |change: &dyn Fn(&mut Square1)| -> Vec<u16> {
+            (0..PERM_SIZE).map(|ep| {
+                let mut sq1 = SqSqCoord {co: 0, cp: 0, ep}.to_square1();
+                change(&mut sq1);
+                SqSqCoord::new(&sq1).ep as u16
+            }).collect()
+        };
+        Self {
+            flip: step(&|sq1| sq1.flip_layers()),
+            up_rot: std::array::from_fn(|rot| step(&|sq1| sq1.turn_layers(&(rot * 2, 0)))),
+            down_rot: std::array::from_fn(|rot| step(&|sq1| sq1.turn_layers(&(0, rot * 2)))),
+            black_cycle: std::array::from_fn(|offset| step(&|sq1| sq1.cycle_colors(&(offset as u8, 0)))),
+            white_cycle: std::array::from_fn(|offset| step(&|sq1| sq1.cycle_colors(&(0, offset as u8))))
+        }
+    }
+}
+
 const fn get_symmetric_actions(co: usize) -> &'static [Symmetry] {
     match co {
         0 => CO_0,
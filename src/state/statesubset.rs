@@ -0,0 +1,244 @@
+use std::fmt;
+
+use crate::square1::Square1;
+
+use super::State;
+use super::statecs::StateCS;
+use super::statesqsq::StateSqSq;
+use super::statevandenbergh::{gen_next_positions_sqsq, get_layers};
+
+// Subsets only look at positions in cube shape, like the Vandenbergh and Lin steps.
+// Turning a layer is free before the next slice, so each layer is read in the rotation
+// with the lowest labels, that is the only symmetry every subset has.
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Subset {
+    pub name: String,
+    // Pieces that are told apart
+    pub tracked: Vec<u8>,
+    // Pieces where only the layer color matters, all other pieces are ignored
+    pub oriented: Vec<u8>
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SubsetError {
+    UnknownPiece(u8),
+    DuplicatePiece(u8),
+    // The name can't be used as a file name
+    InvalidName(String),
+    NotCubeShape
+}
+
+impl fmt::Display for SubsetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SubsetError::UnknownPiece(piece) => write!(f, "piece {} doesn't exist", piece),
+            SubsetError::DuplicatePiece(piece) => write!(f, "piece {} appears more than once", piece),
+            SubsetError::InvalidName(name) => write!(f, "\"{}\" is not a valid table name", name),
+            SubsetError::NotCubeShape => write!(f, "Square-1 is not in cube shape")
+        }
+    }
+}
+
+// The labels of the pieces and the counts for each kind, corners first
+pub struct SubsetConfig {
+    subset: Subset,
+    labels: [u8; 16],
+    counts: [Counts; 2]
+}
+
+#[derive(Clone, Copy)]
+struct Counts {
+    tracked: usize,
+    up: usize,
+    down: usize
+}
+
+impl Counts {
+    // Tracked pieces in order, then the oriented pieces by color
+    fn get_size(&self) -> usize {
+        let free = 8 - self.tracked;
+        (free + 1..=8).product::<usize>() * binomial(free, self.up) * binomial(free - self.up, self.down)
+    }
+}
+
+pub struct SubsetState {
+    sq1: Square1,
+    index: usize
+}
+
+impl State for SubsetState {
+    type Config = SubsetConfig;
+
+    const NAME: &str = "subset";
+
+    // Depends on the Subset, see size()
+    const SIZE: usize = 0;
+
+    // A Subset never needs more slices than the whole position in cube shape
+    const MAX_SLICES: u8 = StateSqSq::MAX_SLICES;
+
+    // The table generation only reaches cube shape, SubsetState::try_new checks it
+    fn new_with(sq1: Square1, config: &SubsetConfig) -> Self {
+        Self::try_new(sq1, config).expect("SubsetState needs cube shape")
+    }
+
+    fn get_index(&self) -> usize {
+        self.index
+    }
+
+    fn get_symmetric_indecies(&mut self) -> Vec<usize> {
+        vec![]
+    }
+
+    fn get_square1_num(&self) -> u64 {
+        self.sq1.get_num()
+    }
+
+    fn gen_next_positions(sq1num: u64) -> Vec<u64> {
+        gen_next_positions_sqsq(sq1num)
+    }
+
+    fn size(config: &SubsetConfig) -> usize {
+        config.counts.iter().map(Counts::get_size).product()
+    }
+
+    fn name(config: &SubsetConfig) -> String {
+        Self::NAME.to_string() + "_" + &config.subset.name
+    }
+}
+
+impl SubsetState {
+    pub fn try_new(sq1: Square1, config: &SubsetConfig) -> Result<Self, SubsetError> {
+        // get_layers expects corners and edges to alternate
+        if StateCS::new(sq1.clone()).get_cubeshape() != 0 {
+            return Err(SubsetError::NotCubeShape)
+        }
+        let [up, down] = get_layers(&sq1, |piece| config.labels[piece as usize]);
+        let pieces: Vec<u8> = up.into_iter().chain(down).collect();
+        let index = [0, 1].into_iter().fold(0, |index, kind| {
+            let labels: Vec<u8> = pieces.iter().skip(kind).step_by(2).copied().collect();
+            let counts = config.counts[kind];
+            index * counts.get_size() + get_rank(&labels, counts)
+        });
+        Ok(Self {sq1, index})
+    }
+}
+
+impl SubsetConfig {
+    // Tracked pieces get their place in the list, oriented pieces the next two labels by color
+    pub fn new(subset: Subset) -> Result<Self, SubsetError> {
+        if subset.name.is_empty() || !subset.name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+            return Err(SubsetError::InvalidName(subset.name))
+        }
+        let mut seen = [false; 16];
+        for &piece in subset.tracked.iter().chain(&subset.oriented) {
+            if piece >= 16 {
+                return Err(SubsetError::UnknownPiece(piece))
+            }
+            if seen[piece as usize] {
+                return Err(SubsetError::DuplicatePiece(piece))
+            }
+            seen[piece as usize] = true;
+        }
+
+        let mut counts = [Counts { tracked: 0, up: 0, down: 0 }; 2];
+        let mut labels = [0; 16];
+        for &piece in &subset.tracked {
+            let counts = &mut counts[(piece & 1) as usize];
+            labels[piece as usize] = counts.tracked as u8;
+            counts.tracked += 1;
+        }
+        for &piece in &subset.oriented {
+            let counts = &mut counts[(piece & 1) as usize];
+            if piece < 8 {
+                counts.up += 1;
+            } else {
+                counts.down += 1;
+            }
+        }
+        for piece in 0..16 {
+            let counts = counts[(piece & 1) as usize];
+            if !subset.tracked.contains(&piece) {
+                labels[piece as usize] = counts.tracked as u8 + if !subset.oriented.contains(&piece) {2} else if piece < 8 {0} else {1};
+            }
+        }
+        Ok(Self {subset, labels, counts})
+    }
+
+    pub fn get_subset(&self) -> &Subset {
+        &self.subset
+    }
+}
+
+// Ranks the slots of the tracked pieces, then the up and down colored oriented pieces
+fn get_rank(labels: &[u8], counts: Counts) -> usize {
+    let mut free: Vec<usize> = (0..labels.len()).collect();
+    let mut rank = 0;
+    for label in 0..counts.tracked {
+        let pos = free.iter().position(|&slot| labels[slot] == label as u8).expect("Tracked piece is missing");
+        rank = rank * free.len() + pos;
+        free.remove(pos);
+    }
+    for (label, count) in [(counts.tracked, counts.up), (counts.tracked + 1, counts.down)] {
+        let chosen: Vec<usize> = (0..free.len()).filter(|&pos| labels[free[pos]] == label as u8).collect();
+        let combination: usize = chosen.iter().enumerate().map(|(i, &pos)| binomial(pos, i + 1)).sum();
+        rank = rank * binomial(free.len(), count) + combination;
+        free.retain(|&slot| labels[slot] != label as u8);
+    }
+    rank
+}
+
+pub(super) fn binomial(n: usize, k: usize) -> usize {
+    if k > n {
+        return 0
+    }
+    (0..k).fold(1, |result, i| result * (n - i) / (i + 1))
+}
+
+#[cfg(test)]
+mod test {
+    use rand::seq::SliceRandom;
+
+    use crate::{square1::Square1, state::{statelin::StateFB, State}};
+
+    use super::{Subset, SubsetConfig, SubsetError, SubsetState};
+
+    #[test]
+    pub fn test_subset() {
+        let subset = |tracked: Vec<u8>, oriented: Vec<u8>| Subset { name: String::from("fb"), tracked, oriented };
+        assert_eq!(SubsetConfig::new(subset(vec![8, 16], vec![])).err(), Some(SubsetError::UnknownPiece(16)));
+        assert_eq!(SubsetConfig::new(subset(vec![8, 10], vec![10])).err(), Some(SubsetError::DuplicatePiece(10)));
+
+        // The first block subset tells apart the same positions as StateFB
+        let fb = SubsetConfig::new(subset(vec![8, 10, 12, 14, 9, 11, 13], vec![])).unwrap();
+        assert_eq!(SubsetState::size(&fb), 1680 * 336);
+        assert_eq!(SubsetState::name(&fb), "subset_fb");
+
+        let solved = Square1::solved();
+        let mut turned = solved.clone();
+        turned.turn_layers(&(2, 4));
+        assert_eq!(SubsetState::new_with(solved.clone(), &fb).get_index(), SubsetState::new_with(turned, &fb).get_index());
+        let mut sliced = solved.clone();
+        sliced.turn_slice().unwrap();
+        assert!(matches!(SubsetState::try_new(sliced, &fb), Err(SubsetError::NotCubeShape)));
+
+        let mut rng = rand::rng();
+        let mut positions = vec![solved];
+        for _ in 0..200 {
+            let mut corners: Vec<u8> = (0..8).map(|i| i * 2).collect();
+            let mut edges: Vec<u8> = (0..8).map(|i| i * 2 + 1).collect();
+            corners.shuffle(&mut rng);
+            edges.shuffle(&mut rng);
+            positions.push(Square1::from_permutations(&corners, &edges));
+        }
+        for a in &positions {
+            let index = SubsetState::new_with(a.clone(), &fb).get_index();
+            assert!(index < SubsetState::size(&fb));
+            for b in &positions {
+                let same_fb = StateFB::new(a.clone()).get_index() == StateFB::new(b.clone()).get_index();
+                assert_eq!(index == SubsetState::new_with(b.clone(), &fb).get_index(), same_fb);
+            }
+        }
+    }
+}
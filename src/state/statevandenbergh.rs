@@ -0,0 +1,158 @@
+use crate::square1::Square1;
+
+use super::State;
+
+// The Vandenbergh steps only look at positions in cube shape. Turning a layer is free before
+// the next slice, so each layer is read from the corner, that gives the lowest labels.
+// MAX_SLICES is the deepest count the generation finds.
+
+// Which corners are in the right layer
+pub struct StateCO {
+    sq1: Square1,
+    index: usize
+}
+
+// Which corners and edges are in the right layer
+pub struct StateEO {
+    sq1: Square1,
+    index: usize
+}
+
+// Where the corners are and which edges are in the right layer
+pub struct StateCP {
+    sq1: Square1,
+    index: usize
+}
+
+impl State for StateCO {
+    type Config = ();
+
+    const NAME: &str = "co";
+
+    const SIZE: usize = 256;
+
+    const MAX_SLICES: u8 = 3;
+
+    fn new_with(sq1: Square1, _: &()) -> Self {
+        let [up, down] = get_layers(&sq1, |piece| if piece & 1 == 0 {piece >> 3} else {0});
+        let index = (get_bits(&up[0..8].iter().step_by(2).copied().collect::<Vec<u8>>()) << 4)
+            + get_bits(&down[0..8].iter().step_by(2).copied().collect::<Vec<u8>>());
+        Self {sq1, index}
+    }
+
+    fn get_index(&self) -> usize {
+        self.index
+    }
+
+    fn get_symmetric_indecies(&mut self) -> Vec<usize> {
+        vec![]
+    }
+
+    fn get_square1_num(&self) -> u64 {
+        self.sq1.get_num()
+    }
+
+    fn gen_next_positions(sq1num: u64) -> Vec<u64> {
+        gen_next_positions_sqsq(sq1num)
+    }
+}
+
+impl State for StateEO {
+    type Config = ();
+
+    const NAME: &str = "eo";
+
+    const SIZE: usize = 65_536;
+
+    const MAX_SLICES: u8 = 6;
+
+    fn new_with(sq1: Square1, _: &()) -> Self {
+        let [up, down] = get_layers(&sq1, |piece| piece >> 3);
+        let index = (get_bits(&up) << 8) + get_bits(&down);
+        Self {sq1, index}
+    }
+
+    fn get_index(&self) -> usize {
+        self.index
+    }
+
+    fn get_symmetric_indecies(&mut self) -> Vec<usize> {
+        vec![]
+    }
+
+    fn get_square1_num(&self) -> u64 {
+        self.sq1.get_num()
+    }
+
+    fn gen_next_positions(sq1num: u64) -> Vec<u64> {
+        gen_next_positions_sqsq(sq1num)
+    }
+}
+
+impl State for StateCP {
+    type Config = ();
+
+    const NAME: &str = "cp";
+
+    const SIZE: usize = 10_321_920;
+
+    const MAX_SLICES: u8 = 7;
+
+    fn new_with(sq1: Square1, _: &()) -> Self {
+        // Corners keep their number, edges only show their layer, so corners always sort first
+        let [up, down] = get_layers(&sq1, |piece| if piece & 1 == 0 {piece / 2} else {8 + (piece >> 3)});
+        let corners: Vec<u8> = up.iter().chain(&down).step_by(2).copied().collect();
+        let edges: Vec<u8> = up.iter().chain(&down).skip(1).step_by(2).map(|label| label - 8).collect();
+
+        let index = (get_permutation(&corners) << 8) + get_bits(&edges);
+        Self {sq1, index}
+    }
+
+    fn get_index(&self) -> usize {
+        self.index
+    }
+
+    fn get_symmetric_indecies(&mut self) -> Vec<usize> {
+        vec![]
+    }
+
+    fn get_square1_num(&self) -> u64 {
+        self.sq1.get_num()
+    }
+
+    fn gen_next_positions(sq1num: u64) -> Vec<u64> {
+        gen_next_positions_sqsq(sq1num)
+    }
+}
+
+// Labels both layers starting at the corner, that gives the lowest labels
+pub(super) fn get_layers(sq1: &Square1, label: impl Fn(u8) -> u8) -> [Vec<u8>; 2] {
+    [0, 8].map(|start| {
+        let first_corner = start + (sq1.pieces[start] & 1) as usize;
+        let labels: Vec<u8> = (0..8).map(|i| label(sq1.pieces[start + (first_corner - start + i) % 8])).collect();
+        (0..4).map(|rot| [&labels[rot * 2..], &labels[..rot * 2]].concat()).min().unwrap()
+    })
+}
+
+pub(super) fn get_bits(labels: &[u8]) -> usize {
+    labels.iter().fold(0, |bits, &label| (bits << 1) + label as usize)
+}
+
+// Lehmer code of distinct labels
+pub(super) fn get_permutation(labels: &[u8]) -> usize {
+    (0..labels.len()).fold(0, |perm, i| {
+        let smaller = labels[i + 1..].iter().filter(|&&label| label < labels[i]).count();
+        perm * (labels.len() - i) + smaller
+    })
+}
+
+// Only layer turns are symmetries here, so the turns get_unique_turns_sqsq leaves out are needed
+pub(super) fn gen_next_positions_sqsq(sq1num: u64) -> Vec<u64> {
+    let base: Square1 = Square1::from_num(sq1num);
+    base.get_all_turns_sqsq().map(|turn: (usize, usize)| {
+        let mut adj: Square1 = base.clone();
+        adj.turn_layers(&turn);
+        adj.turn_slice().expect("All Turns is wrong");
+        adj.get_num()
+    }).to_vec()
+}
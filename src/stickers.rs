@@ -0,0 +1,220 @@
+use std::fmt;
+
+use crate::square1::{Square1, ValidationError};
+
+// Side faces of every piece, clockwise as seen from the top
+// The top layer starts at the front cut, the bottom layer at the back cut
+pub(crate) const PIECE_SIDES: [&[Face]; 16] = [
+    &[Face::Front, Face::Left], &[Face::Left], &[Face::Left, Face::Back], &[Face::Back],
+    &[Face::Back, Face::Right], &[Face::Right], &[Face::Right, Face::Front], &[Face::Front],
+    &[Face::Back, Face::Right], &[Face::Right], &[Face::Right, Face::Front], &[Face::Front],
+    &[Face::Front, Face::Left], &[Face::Left], &[Face::Left, Face::Back], &[Face::Back],
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Face {
+    Up,
+    Down,
+    Front,
+    Right,
+    Back,
+    Left,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ColorScheme {
+    pub up: char,
+    pub down: char,
+    pub front: char,
+    pub right: char,
+    pub back: char,
+    pub left: char,
+}
+
+impl Default for ColorScheme {
+    fn default() -> Self {
+        Self { up: 'W', down: 'Y', front: 'G', right: 'R', back: 'B', left: 'O' }
+    }
+}
+
+impl ColorScheme {
+    fn get_color(&self, face: Face) -> char {
+        match face {
+            Face::Up => self.up,
+            Face::Down => self.down,
+            Face::Front => self.front,
+            Face::Right => self.right,
+            Face::Back => self.back,
+            Face::Left => self.left,
+        }
+    }
+
+    // Stickers of the piece, starting with the up or down color
+    pub(crate) fn get_stickers(&self, piece: u8) -> Vec<char> {
+        let layer = if piece < 8 {Face::Up} else {Face::Down};
+        let mut stickers = vec![self.get_color(layer)];
+        stickers.extend(PIECE_SIDES[piece as usize].iter().map(|&face| self.get_color(face)));
+        stickers
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PieceError {
+    UnknownColor(char),
+    UnknownPiece(String),
+    // The stickers of a corner are in mirrored order
+    TwistedPiece(String),
+    DuplicatePiece(String),
+    PieceCount(usize),
+    // The layers are not divided by exactly one - or /
+    MissingDivide,
+    LayerAngle(u8),
+    Invalid(ValidationError),
+}
+
+impl fmt::Display for PieceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PieceError::UnknownColor(color) => write!(f, "color {} is not in the color scheme", color),
+            PieceError::UnknownPiece(piece) => write!(f, "no piece looks like {}", piece),
+            PieceError::TwistedPiece(piece) => write!(f, "stickers of {} are in mirrored order", piece),
+            PieceError::DuplicatePiece(piece) => write!(f, "piece {} appears more than once", piece),
+            PieceError::PieceCount(count) => write!(f, "found {} pieces instead of 16", count),
+            PieceError::MissingDivide => write!(f, "layers have to be divided by one - or /"),
+            PieceError::LayerAngle(angle) => write!(f, "layer spans {} instead of 12 units", angle),
+            PieceError::Invalid(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl Square1 {
+    // Each layer lists its pieces clockwise from the cut, a piece starts with its top or bottom color,
+    // so solved is WGO WO WOB WB WBR WR WRG WG and YBR YR YRG YG YGO YO YOB YB
+    pub fn from_stickers(top: &str, bottom: &str, scheme: &ColorScheme) -> Result<Self, PieceError> {
+        let colors = [scheme.up, scheme.down, scheme.front, scheme.right, scheme.back, scheme.left];
+        let pieces: Vec<&str> = top.split([' ', ',']).chain(bottom.split([' ', ',']))
+            .filter(|piece| !piece.is_empty())
+            .collect();
+        let top_len = top.split([' ', ',']).filter(|piece| !piece.is_empty()).count();
+
+        let mut found = Vec::new();
+        for piece in &pieces {
+            let stickers: Vec<char> = piece.chars().map(|c| c.to_ascii_uppercase()).collect();
+            if let Some(&color) = stickers.iter().find(|color| !colors.iter().any(|c| c.eq_ignore_ascii_case(color))) {
+                return Err(PieceError::UnknownColor(color))
+            }
+            let mut sorted = stickers.clone();
+            sorted.sort();
+            let matching = (0..16).find(|&p| {
+                let mut solved: Vec<char> = scheme.get_stickers(p).iter().map(|c| c.to_ascii_uppercase()).collect();
+                solved.sort();
+                solved == sorted
+            }).ok_or(PieceError::UnknownPiece(piece.to_string()))?;
+
+            // Turning a piece keeps the cyclic order of its stickers
+            let solved: Vec<char> = scheme.get_stickers(matching).iter().map(|c| c.to_ascii_uppercase()).collect();
+            if !(0..solved.len()).any(|shift| solved[shift..].iter().chain(&solved[..shift]).eq(&stickers)) {
+                return Err(PieceError::TwistedPiece(piece.to_string()))
+            }
+            if found.contains(&matching) {
+                return Err(PieceError::DuplicatePiece(piece.to_string()))
+            }
+            found.push(matching);
+        }
+        Self::from_pieces(found, top_len)
+    }
+
+    // Reads A1B2C3D4-E5F6G7H8, with / instead of - if the middle layer is flipped
+    pub fn from_letters(letters: &str) -> Result<(Self, bool), PieceError> {
+        let letters: String = letters.chars().filter(|c| !c.is_whitespace()).collect();
+        let Some(divide) = letters.find(['-', '/']).filter(|_| letters.matches(['-', '/']).count() == 1) else {
+            return Err(PieceError::MissingDivide)
+        };
+        let bar_solved = letters[divide..].starts_with('-');
+
+        let mut found = Vec::new();
+        for c in letters.chars().filter(|&c| c != '-' && c != '/') {
+            let piece = match c.to_ascii_uppercase() {
+                corner @ 'A'..='H' => (corner as u8 - b'A') * 2,
+                edge @ '1'..='8' => (edge as u8 - b'1') * 2 + 1,
+                _ => return Err(PieceError::UnknownPiece(c.to_string())),
+            };
+            if found.contains(&piece) {
+                return Err(PieceError::DuplicatePiece(c.to_string()))
+            }
+            found.push(piece);
+        }
+        Ok((Self::from_pieces(found, divide)?, bar_solved))
+    }
+
+    pub fn to_letters(&self, bar_solved: bool) -> String {
+        let divide = self.get_divide();
+        let mut letters = String::new();
+        for (i, &piece) in self.pieces.iter().enumerate() {
+            if i == divide {
+                letters.push(if bar_solved {'-'} else {'/'});
+            }
+            letters.push(if piece % 2 == 0 {(b'A' + piece / 2) as char} else {(b'1' + piece / 2) as char});
+        }
+        letters
+    }
+
+    // The first top_len pieces are the top layer
+    fn from_pieces(pieces: Vec<u8>, top_len: usize) -> Result<Self, PieceError> {
+        let pieces: [u8; 16] = pieces.try_into().map_err(|pieces: Vec<u8>| PieceError::PieceCount(pieces.len()))?;
+        let (top, bottom) = pieces.split_at(top_len.min(16));
+        for layer in [top, bottom] {
+            let angle: u8 = layer.iter().map(|&piece| 2 - (piece & 1)).sum();
+            if angle != 12 {
+                return Err(PieceError::LayerAngle(angle))
+            }
+        }
+        let square1 = Self::from_arr(pieces);
+        square1.validate().map_err(PieceError::Invalid)?;
+        Ok(square1)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{square1::Square1, stickers::{ColorScheme, PieceError}};
+
+    #[test]
+    pub fn test_from_stickers() {
+        let scheme = ColorScheme::default();
+        let solved = Square1::from_stickers("WGO WO WOB WB WBR WR WRG WG", "YBR YR YRG YG YGO YO YOB YB", &scheme);
+        assert_eq!(solved, Ok(Square1::solved()));
+
+        // Corners may start at any sticker, as long as the order stays clockwise
+        let turned = Square1::from_stickers("WR RGW WG GOW, wo obw wb brw", "ybr yr yrg yg ygo yo yob yb", &scheme);
+        assert_eq!(turned, Ok(Square1::from_arr([5, 6, 7, 0, 1, 2, 3, 4, 8, 9, 10, 11, 12, 13, 14, 15])));
+
+        let top = "WGO WO WOB WB WBR WR WRG WG";
+        assert_eq!(Square1::from_stickers(top, "YBR YR YRG YG YGO YO YOB YP", &scheme), Err(PieceError::UnknownColor('P')));
+        assert_eq!(Square1::from_stickers(top, "YBR YR YRG YG YGO YO YOB YW", &scheme), Err(PieceError::UnknownPiece("YW".to_string())));
+        assert_eq!(Square1::from_stickers(top, "YRB YR YRG YG YGO YO YOB YB", &scheme), Err(PieceError::TwistedPiece("YRB".to_string())));
+        assert_eq!(Square1::from_stickers(top, "YBR YR YRG YG YGO YO YOB YR", &scheme), Err(PieceError::DuplicatePiece("YR".to_string())));
+        assert_eq!(Square1::from_stickers(top, "YBR YR YRG YG YGO YO YOB", &scheme), Err(PieceError::PieceCount(15)));
+
+        // Both layers have to span 12 units
+        assert_eq!(Square1::from_stickers("WGO WO WOB WB WBR WR WRG WG YB", "YBR YR YRG YG YGO YO YOB", &scheme), Err(PieceError::LayerAngle(13)));
+    }
+
+    #[test]
+    pub fn test_letters() {
+        assert_eq!(Square1::from_letters("A1B2C3D4-E5F6G7H8"), Ok((Square1::solved(), true)));
+        for _ in 0..10 {
+            let (square1, bar_solved) = Square1::scrambled();
+            assert_eq!(Square1::from_letters(&square1.to_letters(bar_solved)), Ok((square1, bar_solved)));
+        }
+        assert_eq!(Square1::from_letters("A1B2C3D4E5F6G7H8"), Err(PieceError::MissingDivide));
+        assert_eq!(Square1::from_letters("A1B2C3D4/E5F6G7H9"), Err(PieceError::UnknownPiece("9".to_string())));
+        assert_eq!(Square1::from_letters("A1B2C3D4/E5F6G7A8"), Err(PieceError::DuplicatePiece("A".to_string())));
+        assert_eq!(Square1::from_letters("A1B2-C3D4-E5F6G7H8"), Err(PieceError::MissingDivide));
+
+        // The divide has to sit where the top layer spans 12 units
+        assert_eq!(Square1::from_letters("1ABCDEF2-GH345678"), Err(PieceError::LayerAngle(14)));
+        assert_eq!(Square1::from_letters("1ABCDE2-FGH345678").map(|(square1, _)| square1.to_letters(true)), Ok("1ABCDE2-FGH345678".to_string()));
+        assert_eq!(Square1::from_letters("A1B2C3D-4E5F6G7H8"), Err(PieceError::LayerAngle(11)));
+    }
+}
@@ -0,0 +1,168 @@
+use std::{fmt, fs::{self, File}, io::{self, BufReader, BufWriter, Read, Write}, path::Path};
+
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle};
+use lz4_flex::frame::{FrameDecoder, FrameEncoder};
+
+use crate::state::{stateall::StateAll, State};
+
+// A compressed file starts with the magic bytes, the length of the table as a little endian u64
+// and the CRC-32 of the table as a little endian u32, followed by the table as one LZ4 frame.
+// Importing decompresses chunk by chunk straight into the table, so the file is never held
+// in memory next to it.
+
+const MAGIC: &[u8; 4] = b"SQ1T";
+
+const HEADER_SIZE: usize = 16;
+
+const CHUNK_SIZE: usize = 1 << 20;
+
+// The all table is the largest one
+const MAX_TABLE_SIZE: usize = StateAll::SIZE.div_ceil(2);
+
+// LZ4 can't shrink data by more than this
+const MAX_RATIO: u64 = 256;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CompressedError {
+    // The file couldn't be read or the LZ4 frame is broken
+    Io(String),
+    NotCompressed,
+    // The header gives a length no table has or the file can't hold
+    TableSize(u64),
+    TrailingData,
+    Checksum {expected: u32, found: u32}
+}
+
+impl fmt::Display for CompressedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CompressedError::Io(error) => write!(f, "{}", error),
+            CompressedError::NotCompressed => write!(f, "file is not a compressed table"),
+            CompressedError::TableSize(len) => write!(f, "file can't hold a table of {} bytes", len),
+            CompressedError::TrailingData => write!(f, "file holds more data than the table"),
+            CompressedError::Checksum {expected, found} => write!(f, "checksum is {:08x} instead of {:08x}", found, expected)
+        }
+    }
+}
+
+impl From<io::Error> for CompressedError {
+    fn from(error: io::Error) -> Self {
+        CompressedError::Io(error.to_string())
+    }
+}
+
+pub fn get_compressed_file_name(file_name: &str) -> String {
+    String::from(file_name) + ".lz4"
+}
+
+pub fn export_table(table: &[u8], file_name: &str, display_progress: bool) -> io::Result<()> {
+    if let Some(dir) = Path::new(file_name).parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut writer = BufWriter::new(File::create(file_name)?);
+    writer.write_all(MAGIC)?;
+    writer.write_all(&(table.len() as u64).to_le_bytes())?;
+    writer.write_all(&crc32fast::hash(table).to_le_bytes())?;
+
+    let pb = new_progress_bar(table.len(), "Compressing", display_progress);
+    let mut encoder = FrameEncoder::new(writer);
+    for chunk in table.chunks(CHUNK_SIZE) {
+        encoder.write_all(chunk)?;
+        pb.inc(chunk.len() as u64);
+    }
+    encoder.finish().map_err(io::Error::from)?.flush()?;
+    pb.finish();
+    Ok(())
+}
+
+pub fn import_table(file_name: &str, display_progress: bool) -> Result<Vec<u8>, CompressedError> {
+    let file = File::open(file_name)?;
+    let file_len = file.metadata()?.len();
+    let mut reader = BufReader::new(file);
+    let mut header = [0; HEADER_SIZE];
+    reader.read_exact(&mut header)?;
+    if &header[..4] != MAGIC {
+        return Err(CompressedError::NotCompressed)
+    }
+    // The length is checked before the table gets allocated
+    let len = u64::from_le_bytes(header[4..12].try_into().unwrap());
+    if len > MAX_TABLE_SIZE as u64 || len > file_len * MAX_RATIO {
+        return Err(CompressedError::TableSize(len))
+    }
+    let len = len as usize;
+    let expected = u32::from_le_bytes(header[12..].try_into().unwrap());
+
+    let pb = new_progress_bar(len, "Decompressing", display_progress);
+    let mut decoder = FrameDecoder::new(reader);
+    let mut hasher = crc32fast::Hasher::new();
+    let mut table = vec![0; len];
+    for chunk in table.chunks_mut(CHUNK_SIZE) {
+        decoder.read_exact(chunk)?;
+        hasher.update(chunk);
+        pb.inc(chunk.len() as u64);
+    }
+    if decoder.read(&mut [0])? != 0 {
+        return Err(CompressedError::TrailingData)
+    }
+    pb.finish();
+
+    let found = hasher.finalize();
+    if found != expected {
+        return Err(CompressedError::Checksum {expected, found})
+    }
+    Ok(table)
+}
+
+fn new_progress_bar(len: usize, msg: &'static str, display_progress: bool) -> ProgressBar {
+    let pb = ProgressBar::with_draw_target(Some(len as u64), if display_progress {ProgressDrawTarget::stderr()} else {ProgressDrawTarget::hidden()});
+    pb.set_style(ProgressStyle::with_template(
+        "[{elapsed_precise}] Table: {bar:40.cyan/blue} {percent_precise:>7}% {msg}"
+    )
+    .unwrap()
+    .progress_chars("#>-"));
+    pb.set_message(msg);
+    pb
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use super::{export_table, import_table, CompressedError};
+
+    #[test]
+    pub fn test_compressed() {
+        let file_name = std::env::temp_dir().join(format!("square1_compressed_{}.bin.lz4", std::process::id()));
+        let file_name = file_name.to_str().unwrap();
+        // Runs of slice counts, like in the real tables
+        let table: Vec<u8> = (0..3_000_000u64).map(|i| ((i / 50).wrapping_mul(0x9E37_79B9_7F4A_7C15) >> 60) as u8 * 17).collect();
+        export_table(&table, file_name, false).unwrap();
+        assert!(fs::metadata(file_name).unwrap().len() < table.len() as u64);
+        assert_eq!(import_table(file_name, false), Ok(table.clone()));
+
+        // A wrong checksum in the header
+        let mut bytes = fs::read(file_name).unwrap();
+        bytes[12] ^= 1;
+        fs::write(file_name, &bytes).unwrap();
+        assert!(matches!(import_table(file_name, false), Err(CompressedError::Checksum {..})));
+        bytes[12] ^= 1;
+
+        // A cut off frame
+        fs::write(file_name, &bytes[..bytes.len() / 2]).unwrap();
+        assert!(matches!(import_table(file_name, false), Err(CompressedError::Io(_))));
+
+        // A length no table has
+        let mut huge = bytes.clone();
+        huge[4..12].copy_from_slice(&u64::MAX.to_le_bytes());
+        fs::write(file_name, &huge).unwrap();
+        assert_eq!(import_table(file_name, false), Err(CompressedError::TableSize(u64::MAX)));
+        huge[4..12].copy_from_slice(&(bytes.len() as u64 * 1000).to_le_bytes());
+        fs::write(file_name, &huge).unwrap();
+        assert!(matches!(import_table(file_name, false), Err(CompressedError::TableSize(_))));
+
+        // The table itself isn't compressed
+        fs::write(file_name, &table).unwrap();
+        assert_eq!(import_table(file_name, false), Err(CompressedError::NotCompressed));
+        fs::remove_file(file_name).unwrap();
+    }
+}
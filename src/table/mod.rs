@@ -1,6 +1,8 @@
 pub mod postable;
+pub mod packed;
+pub mod compressed;
 
-use std::{fs::{self}, str::FromStr, sync::{Arc, RwLock}, time::{Duration, Instant}};
+use std::{fs::{self}, path::Path, str::FromStr, sync::{Arc, RwLock}, time::{Duration, Instant}};
 
 use indicatif::{MultiProgress, ProgressBar, ProgressDrawTarget, ProgressStyle};
 use postable::PosTable;
@@ -9,15 +11,20 @@ use rayon::iter::{IntoParallelIterator, IntoParallelRefMutIterator, ParallelIter
 use crate::{square1::Square1, state::State};
 
 pub struct SliceCountTable <S: State + Sync> {
+    config: S::Config,
     pb_table: ProgressBar,
     pb_closed: ProgressBar,
     _marker: std::marker::PhantomData<S>
 }
 
 impl<S: State + Sync> SliceCountTable<S> {
-    pub fn new(display_progress: bool) -> Self {
+    pub fn new(display_progress: bool) -> Self where S::Config: Default {
+        Self::with_config(S::Config::default(), display_progress)
+    }
+
+    pub fn with_config(config: S::Config, display_progress: bool) -> Self {
         let _multipb = if display_progress {MultiProgress::new()} else {MultiProgress::with_draw_target(ProgressDrawTarget::hidden())};
-        let pb_table = _multipb.add(ProgressBar::new(S::SIZE as u64));
+        let pb_table = _multipb.add(ProgressBar::new(S::size(&config) as u64));
         let pb_closed = _multipb.add(ProgressBar::new(1));
 
         let style_table: ProgressStyle = ProgressStyle::with_template(
@@ -36,6 +43,7 @@ impl<S: State + Sync> SliceCountTable<S> {
         pb_closed.set_message(format!("{}", 0));
 
         Self {
+            config,
             pb_table,
             pb_closed,
             _marker: std::marker::PhantomData
@@ -47,15 +55,15 @@ impl<S: State + Sync> SliceCountTable<S> {
         let now = Instant::now();
 
         // Creates empty Slice Count Table
-        let shared_table: Arc<RwLock<Vec<u8>>> = Arc::new(RwLock::new(vec![255 as u8; (S::SIZE + 1) / 2]));
+        let shared_table: Arc<RwLock<Vec<u8>>> = Arc::new(RwLock::new(vec![255 as u8; (S::size(&self.config) + 1) / 2]));
 
         // Creates empty closed Table
         let mut closed = vec![];
 
         // Fills in the solved State and adds first closed Position
         let solved = Square1::solved();
-        closed.push(solved.get_num());
-        let state = S::new(solved);
+        closed.push(S::get_position_num(&solved));
+        let state = S::new_with(solved, &self.config);
         let _ = Self::write_shared(&shared_table, state.get_index(), 0);
         self.pb_table.inc(1);
 
@@ -74,7 +82,7 @@ impl<S: State + Sync> SliceCountTable<S> {
                 // Opens the next Positions
                 S::gen_next_positions(curr_square1).into_iter().filter(|&next_square1| {
                     // Calculates the State for the Position
-                    let mut state = S::new(Square1::from_num(next_square1));
+                    let mut state = S::from_position_num(next_square1, &self.config);
 
                     // Tries to write State to Table
                     match Self::write_shared(&shared_table, state.get_index(), slice_depth) {
@@ -99,7 +107,7 @@ impl<S: State + Sync> SliceCountTable<S> {
         if slice_depth == S::MAX_SLICES && !self.table_is_full() {
             // Shows Progress
             self.pb_table.set_message("Filling rest");
-            self.clear_pb_closed(S::SIZE as u64 - self.pb_table.position(), slice_depth);
+            self.clear_pb_closed(S::size(&self.config) as u64 - self.pb_table.position(), slice_depth);
 
             // Iterates Table
             let mut table = shared_table.write().unwrap();
@@ -147,7 +155,7 @@ impl<S: State + Sync> SliceCountTable<S> {
         {
             let table = shared_table.read().unwrap();
             let _ = fs::create_dir("slice_count_tables");
-            fs::write(Self::get_file_name(), table.clone()).expect("Saving Table failed!");
+            fs::write(Self::get_config_file_name(&self.config), table.clone()).expect("Saving Table failed!");
         }
     }
 
@@ -156,16 +164,16 @@ impl<S: State + Sync> SliceCountTable<S> {
         let now = Instant::now();
 
         // Creates empty Slice Count Table
-        let shared_table: Arc<RwLock<Vec<u8>>> = Arc::new(RwLock::new(vec![255 as u8; (S::SIZE + 1) / 2]));
+        let shared_table: Arc<RwLock<Vec<u8>>> = Arc::new(RwLock::new(vec![255 as u8; (S::size(&self.config) + 1) / 2]));
 
         // Creates empty closed Table
         let mut closed = PosTable::new("closed", 0);
 
         // Fills in the solved State and adds first closed Position
         let solved = Square1::solved();
-        closed.write_chunk(&[solved.get_num()]);
+        closed.write_chunk(&[S::get_position_num(&solved)]);
 
-        let state = S::new(solved);
+        let state = S::new_with(solved, &self.config);
         let _ = Self::write_shared(&shared_table, state.get_index(), 0);
         self.pb_table.inc(1);
 
@@ -187,7 +195,7 @@ impl<S: State + Sync> SliceCountTable<S> {
                     // Opens the next Positions
                     S::gen_next_positions(*curr_square1).into_iter().filter(|&next_square1| {
                         // Calculates the State for the Position
-                        let mut state = S::new(Square1::from_num(next_square1));
+                        let mut state = S::from_position_num(next_square1, &self.config);
     
                         // Tries to write State to Table
                         match Self::write_shared(&shared_table, state.get_index(), slice_depth) {
@@ -218,7 +226,7 @@ impl<S: State + Sync> SliceCountTable<S> {
         if slice_depth == S::MAX_SLICES && !self.table_is_full() {
             // Shows Progress
             self.pb_table.set_message("Filling rest");
-            self.clear_pb_closed(S::SIZE as u64 - self.pb_table.position(), slice_depth);
+            self.clear_pb_closed(S::size(&self.config) as u64 - self.pb_table.position(), slice_depth);
 
             // Iterates Table
             let mut table = shared_table.write().unwrap();
@@ -266,12 +274,12 @@ impl<S: State + Sync> SliceCountTable<S> {
         {
             let table = shared_table.read().unwrap();
             let _ = fs::create_dir("slice_count_tables");
-            fs::write(Self::get_file_name(), table.clone()).expect("Saving Table failed!");
+            fs::write(Self::get_config_file_name(&self.config), table.clone()).expect("Saving Table failed!");
         }
     }
 
     fn table_is_full(&self) -> bool {
-        self.pb_table.position() == S::SIZE as u64
+        self.pb_table.position() == S::size(&self.config) as u64
     }
 
     // Resets the Progressbar for the closed Table
@@ -331,13 +339,28 @@ impl<S: State + Sync> SliceCountTable<S> {
         }
     }
 
-    pub fn read_table_from_file() -> Vec<u8> {
+    // Decompresses the table if only the compressed file exists
+    pub fn read_table_from_file() -> Vec<u8> where S::Config: Default {
         println!("Loading Table");
-        fs::read(Self::get_file_name()).expect("Unable to read file")
+        let file_name = Self::get_file_name();
+        let compressed_file_name = compressed::get_compressed_file_name(&file_name);
+        if !Path::new(&file_name).exists() && Path::new(&compressed_file_name).exists() {
+            return compressed::import_table(&compressed_file_name, true).unwrap_or_else(|error| panic!("Unable to import table: {}", error))
+        }
+        fs::read(file_name).expect("Unable to read file")
+    }
+
+    pub fn file_exists() -> bool where S::Config: Default {
+        let file_name = Self::get_file_name();
+        Path::new(&file_name).exists() || Path::new(&compressed::get_compressed_file_name(&file_name)).exists()
+    }
+
+    pub fn get_file_name() -> String where S::Config: Default {
+        Self::get_config_file_name(&S::Config::default())
     }
 
-    pub fn get_file_name() -> String {
-        String::from_str("slice_count_tables/table_").unwrap() + S::NAME + ".bin"
+    pub fn get_config_file_name(config: &S::Config) -> String {
+        String::from_str("slice_count_tables/table_").unwrap() + &S::name(config) + ".bin"
     }
 }
 
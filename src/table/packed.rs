@@ -0,0 +1,103 @@
+use std::fs;
+
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+use crate::{square1::Square1, state::{stateall::StateAll, State}};
+
+use super::SliceCountTable;
+
+// One slice changes the slice count by at most one, so knowing the slice count modulo 3 of the
+// next Position is enough once the slice count of the current one is known. The slice count of the
+// first Position is found by walking down the table until the solved Position is reached.
+// Four entries share a byte, the first one in the highest bits.
+
+pub struct PackedTable {
+    table: Vec<u8>,
+    solved: usize
+}
+
+impl PackedTable {
+    pub fn from_table(table: &Vec<u8>) -> Self {
+        let size = table.len() * 2;
+        let table = (0..size.div_ceil(4)).into_par_iter().map(|byte| {
+            (0..4).fold(0, |packed, i| {
+                let index = byte * 4 + i;
+                let value = if index < size {SliceCountTable::<StateAll>::read(table, index) % 3} else {0};
+                (packed << 2) + value
+            })
+        }).collect();
+        Self {table, solved: StateAll::new(Square1::solved()).get_index()}
+    }
+
+    pub fn get_slice_count(&self, square1: Square1) -> u8 {
+        let mut position = square1;
+        let mut count = 0;
+        loop {
+            let index = StateAll::new(position.clone()).get_index();
+            if index == self.solved {
+                return count
+            }
+            let closer = (self.read(index) + 2) % 3;
+            position = position.get_all_turns().into_iter().map(|turn| {
+                let mut adj = position.clone();
+                adj.turn_layers(&turn);
+                adj.turn_slice().expect("Couldn't turn slice");
+                adj
+            }).find(|adj| self.read(StateAll::new(adj.clone()).get_index()) == closer).expect("Slice Count Table is wrong");
+            count += 1;
+        }
+    }
+
+    // Slice count of a Position one slice away from a Position with the given slice count
+    pub fn get_next_slice_count(&self, square1: Square1, count: u8) -> u8 {
+        get_next_count(count, self.read(StateAll::new(square1).get_index()))
+    }
+
+    fn read(&self, index: usize) -> u8 {
+        (self.table[index >> 2] >> ((3 - (index & 3)) * 2)) & 3
+    }
+
+    pub fn read_table_from_file() -> Self {
+        println!("Loading Table");
+        let table = fs::read(Self::get_file_name()).expect("Unable to read file");
+        Self {table, solved: StateAll::new(Square1::solved()).get_index()}
+    }
+
+    pub fn write_table_to_file(&self) {
+        let _ = fs::create_dir("slice_count_tables");
+        fs::write(Self::get_file_name(), &self.table).expect("Saving Table failed!");
+    }
+
+    pub fn get_file_name() -> String {
+        String::from("slice_count_tables/table_all_packed.bin")
+    }
+}
+
+// The count that is at most one away from the given one and has the remainder
+fn get_next_count(count: u8, remainder: u8) -> u8 {
+    match (remainder + 3 - count % 3) % 3 {
+        0 => count,
+        1 => count + 1,
+        _ => count - 1
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{get_next_count, PackedTable};
+
+    #[test]
+    pub fn test_packed() {
+        // Nibbles 0 to 11 and 15
+        let table = vec![0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xf0];
+        let packed = PackedTable::from_table(&table);
+        assert_eq!(packed.table.len(), 4);
+        assert_eq!((0..12).map(|index| packed.read(index)).collect::<Vec<u8>>(), vec![0, 1, 2, 0, 1, 2, 0, 1, 2, 0, 1, 2]);
+        assert_eq!(packed.read(13), 0);
+
+        assert_eq!(get_next_count(5, 1), 4);
+        assert_eq!(get_next_count(5, 2), 5);
+        assert_eq!(get_next_count(5, 0), 6);
+        assert_eq!(get_next_count(1, 0), 0);
+    }
+}
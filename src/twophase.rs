@@ -0,0 +1,235 @@
+use std::sync::LazyLock;
+
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+use crate::{solver::{get_abf_turn, get_length, normalise_turn, Solution, SolveError}, square1::Square1, state::{movetable::{SqSqCoord, MOVES}, statecs::{StateCS, CUBESHAPES}, statesqsq::StateSqSq, State}, table::SliceCountTable};
+
+static CS_TABLE: LazyLock<Vec<u8>> = LazyLock::new(SliceCountTable::<StateCS>::read_table_from_file);
+static SQSQ_TABLE: LazyLock<Vec<u8>> = LazyLock::new(SliceCountTable::<StateSqSq>::read_table_from_file);
+
+// Human readable turn of each move and the turn after its slice, that brings a corner back to the start of both layers
+static MOVE_TURNS: LazyLock<Vec<[(i8, i8); 2]>> = LazyLock::new(|| {
+    let base = Square1::solved();
+    base.get_all_turns_sqsq().iter().map(|&turn| {
+        let mut adj = base.clone();
+        adj.turn_layers(&turn);
+        adj.turn_slice().expect("All Turns is wrong");
+        [base.get_human_readable(turn), adj.get_human_readable(get_alignment(&adj))]
+    }).collect()
+});
+
+// Human readable turns, each but the last followed by a slice
+type Path = Vec<(i8, i8)>;
+
+// Extra slices tried in the second phase, if the Slice Count Table leads into dead ends
+const MAX_EXTRA_SLICES: u8 = 4;
+
+pub fn load_tables() {
+    SliceCountTable::<StateCS>::read(&CS_TABLE, 0);
+    SliceCountTable::<StateSqSq>::read(&SQSQ_TABLE, 0);
+}
+
+fn get_cs_count(square1: Square1) -> u8 {
+    let index = StateCS::new(square1).get_index();
+    SliceCountTable::<StateCS>::read(&CS_TABLE, index)
+}
+
+// Slices into cube shape with either parity, taking the other parity from the matching class
+fn get_cs_count_any_parity(square1: Square1) -> u8 {
+    let state = StateCS::new(square1);
+    let count = SliceCountTable::<StateCS>::read(&CS_TABLE, state.get_index());
+    if state.get_cubeshape() < StateCS::SIZE - CUBESHAPES {
+        count.min(SliceCountTable::<StateCS>::read(&CS_TABLE, (1 - state.get_parity()) * CUBESHAPES + state.get_cubeshape()))
+    } else {
+        count
+    }
+}
+
+pub(crate) fn get_sqsq_count(square1: Square1) -> u8 {
+    let index = StateSqSq::new(square1).get_index();
+    SliceCountTable::<StateSqSq>::read(&SQSQ_TABLE, index)
+}
+
+fn get_sqsq_count_coord(coord: SqSqCoord) -> u8 {
+    let index = StateSqSq::from_coord(coord).get_index();
+    SliceCountTable::<StateSqSq>::read(&SQSQ_TABLE, index)
+}
+
+// The layer turn SqSqCoord::new does, to get a corner at the start of both layers
+fn get_alignment(square1: &Square1) -> (usize, usize) {
+    ((square1.pieces[0] & 1) as usize, (square1.pieces[8] & 1) as usize)
+}
+
+fn add_turns(first: (i8, i8), second: (i8, i8)) -> (i8, i8) {
+    normalise_turn((first.0 + second.0, first.1 + second.1))
+}
+
+// Brings the Square-1 into cube shape with even parity, then solves it in cube shape
+pub fn solve_two_phase(square1: Square1, bar_solved: bool) -> Result<Solution, SolveError> {
+    square1.validate().map_err(SolveError::Invalid)?;
+    let cs_slices = get_cs_count(square1.clone());
+    println!("Cube shape in {} slices", cs_slices);
+
+    // Orders the ways into cube shape by the slices the second phase needs
+    let mut candidates: Vec<(u8, Path, Square1)> = get_cubeshape_paths(&square1, cs_slices, Square1::get_unique_turns, &get_cs_count).into_iter().map(|(path, cube)| {
+        let mut slices = get_sqsq_count(cube.clone());
+        if (cs_slices + slices).is_multiple_of(2) != bar_solved {
+            slices += 1;
+        }
+        (slices, path, cube)
+    }).collect();
+    candidates.sort_by_key(|(slices, _, _)| *slices);
+
+    // Finishes the ways with the fewest slices and keeps the lowest turn value
+    let mut best: Option<(u8, u32, Path)> = None;
+    for (slices, mut path, cube) in candidates {
+        if best.as_ref().is_some_and(|(best_slices, _, _)| *best_slices < slices) {
+            break;
+        }
+        let Some((finish_slices, finish_value, finish_path)) = get_sqsq_finish(&cube, slices) else {
+            continue;
+        };
+        let value = path.iter().map(|&readable| get_length(readable) as u32).sum::<u32>() + finish_value;
+        if best.as_ref().is_none_or(|(best_slices, best_value, _)| (finish_slices, value) < (*best_slices, *best_value)) {
+            path.extend(finish_path);
+            best = Some((finish_slices, value, path));
+        }
+    }
+
+    let (_, value, notation) = best.expect("Slice Count Tables are wrong");
+    println!("Solvable in {} slices", notation.len() - 1);
+    println!("Turn Value: {}", value);
+    Ok(Solution { notation })
+}
+
+// All slice optimal ways into cube shape ordered by turn value, each ending with the slice into cube shape
+pub fn solve_cubeshape(square1: Square1, parity_aware: bool) -> Result<Vec<Solution>, SolveError> {
+    square1.validate().map_err(SolveError::Invalid)?;
+    let get_count = if parity_aware {get_cs_count} else {get_cs_count_any_parity};
+    let slices = get_count(square1.clone());
+    // All turns, so that slicing the other half is ordered by its own turn value
+    let mut solutions: Vec<Solution> = get_cubeshape_paths(&square1, slices, Square1::get_all_turns, &get_count).into_iter().map(|(mut notation, _)| {
+        if !notation.is_empty() {
+            notation.push((0, 0));
+        }
+        Solution { notation }
+    }).collect();
+    solutions.sort_by_key(Solution::turn_value);
+    Ok(solutions)
+}
+
+// Collects all slice optimal paths into cube shape and the positions they end in
+fn get_cubeshape_paths(square1: &Square1, slices: u8, get_turns: fn(&Square1) -> Vec<(usize, usize)>, get_count: &(impl Fn(Square1) -> u8 + Sync)) -> Vec<(Path, Square1)> {
+    if slices == 0 {
+        vec![(vec![], square1.clone())]
+    } else {
+        get_turns(square1).into_par_iter().flat_map_iter(|turn| {
+            let mut adj = square1.clone();
+            adj.turn_layers(&turn);
+            adj.turn_slice().expect("Couldn't turn slice");
+            let readable = square1.get_human_readable(turn);
+            let paths = if get_count(adj.clone()) < slices {
+                get_cubeshape_paths(&adj, slices - 1, get_turns, get_count)
+            } else {
+                vec![]
+            };
+            paths.into_iter().map(move |(mut path, cube)| {
+                path.insert(0, readable);
+                (path, cube)
+            })
+        }).collect()
+    }
+}
+
+// Solves a cube shape position in the fewest slices of matching parity
+pub(crate) fn get_sqsq_finish(cube: &Square1, slices: u8) -> Option<(u8, u32, Path)> {
+    let coord = SqSqCoord::new(cube);
+    let alignment = cube.get_human_readable(get_alignment(cube));
+    (0..=MAX_EXTRA_SLICES).step_by(2).find_map(|extra| {
+        get_sqsq_path(coord, alignment, slices + extra).map(|(value, mut path)| {
+            path.reverse();
+            (slices + extra, value, path)
+        })
+    })
+}
+
+// Finds the path with the lowest turn value, that solves the position without leaving cube shape
+//
+// The search runs on the coordinates, the alignment is the turn that still has to be
+// added to the next turn, since the coordinates always start with a corner in both layers.
+fn get_sqsq_path(coord: SqSqCoord, alignment: (i8, i8), slices: u8) -> Option<(u32, Path)> {
+    if slices == 0 {
+        let square1 = coord.to_square1();
+        if square1.pieces[0] > 7 || square1.pieces[8] < 8 {
+            return None
+        }
+        let abf_turn = get_abf_turn(&square1);
+        let mut adj = square1.clone();
+        adj.turn_layers(&abf_turn);
+        if adj == Square1::solved() {
+            let readable = add_turns(alignment, square1.get_human_readable(abf_turn));
+            Some((get_length(readable) as u32, vec![readable]))
+        } else {
+            None
+        }
+    } else {
+        let moves: Vec<usize> = if slices > 1 {SqSqCoord::get_unique_moves().to_vec()} else {(0..MOVES).collect()};
+        moves.into_par_iter().filter_map(|mv| {
+            let adj = coord.apply(mv);
+            if get_sqsq_count_coord(adj) < slices {
+                let [turn, next_alignment] = MOVE_TURNS[mv];
+                get_sqsq_path(adj, next_alignment, slices - 1).map(|(value, mut path)| {
+                    let readable = add_turns(alignment, turn);
+                    path.push(readable);
+                    (value + get_length(readable) as u32, path)
+                })
+            } else {
+                None
+            }
+        }).min_by_key(|(value, _)| *value)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{solver::{verify_solution, Solution}, square1::Square1, state::{statecs::StateCS, State}};
+
+    use super::{get_cs_count, get_cs_count_any_parity, get_cubeshape_paths, solve_cubeshape, solve_two_phase};
+
+    #[test]
+    #[ignore = "needs generated tables"]
+    pub fn test_two_phase() {
+        for _ in 0..5 {
+            let (square1, bar_solved) = Square1::scrambled();
+            let solution = solve_two_phase(square1.clone(), bar_solved).unwrap();
+            assert_eq!(verify_solution(square1, bar_solved, &solution), Ok(()));
+        }
+    }
+    #[test]
+    #[ignore = "needs generated tables"]
+    pub fn test_cubeshape() {
+        for _ in 0..5 {
+            let (square1, _) = Square1::scrambled();
+            assert!(get_cs_count_any_parity(square1.clone()) <= get_cs_count(square1.clone()));
+            for parity_aware in [false, true] {
+                let solutions = solve_cubeshape(square1.clone(), parity_aware).unwrap();
+                assert!(!solutions.is_empty());
+                assert!(solutions.windows(2).all(|pair| pair[0].turn_value() <= pair[1].turn_value()));
+                // The unique turns leave out slicing the other half, which can be cheaper
+                let get_count = if parity_aware {get_cs_count} else {get_cs_count_any_parity};
+                let unique = get_cubeshape_paths(&square1, get_count(square1.clone()), Square1::get_unique_turns, &get_count);
+                assert!(solutions.len() >= unique.len());
+                assert!(unique.iter().all(|(path, _)| Solution { notation: path.clone() }.turn_value() >= solutions[0].turn_value()));
+                for solution in solutions.iter().take(10) {
+                    let mut cube = square1.clone();
+                    cube.apply(solution).unwrap();
+                    assert_eq!(StateCS::new(cube.clone()).get_cubeshape(), 0);
+                    assert_eq!(get_cs_count_any_parity(cube.clone()), 0);
+                    if parity_aware {
+                        assert_eq!(get_cs_count(cube), 0);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,147 @@
+use std::sync::LazyLock;
+
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+use crate::{solver::{get_length, Solution, SolveError}, square1::Square1, state::{statecs::StateCS, statevandenbergh::{StateCO, StateCP, StateEO}, State}, table::SliceCountTable, twophase::{get_sqsq_count, get_sqsq_finish}};
+
+static CO_TABLE: LazyLock<Vec<u8>> = LazyLock::new(SliceCountTable::<StateCO>::read_table_from_file);
+static EO_TABLE: LazyLock<Vec<u8>> = LazyLock::new(SliceCountTable::<StateEO>::read_table_from_file);
+static CP_TABLE: LazyLock<Vec<u8>> = LazyLock::new(SliceCountTable::<StateCP>::read_table_from_file);
+
+// Human readable turns, each followed by a slice
+type Path = Vec<(i8, i8)>;
+
+// In the order they get solved
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Step {
+    CornerOrientation,
+    EdgeOrientation,
+    CornerPermutation,
+    // Also turns the layers into place, which needs even parity
+    EdgePermutation
+}
+
+pub const STEPS: [Step; 4] = [Step::CornerOrientation, Step::EdgeOrientation, Step::CornerPermutation, Step::EdgePermutation];
+
+impl Step {
+    // Slices still needed for the step
+    fn get_count(self, square1: Square1) -> u8 {
+        match self {
+            Step::CornerOrientation => SliceCountTable::<StateCO>::read(&CO_TABLE, StateCO::new(square1).get_index()),
+            Step::EdgeOrientation => SliceCountTable::<StateEO>::read(&EO_TABLE, StateEO::new(square1).get_index()),
+            Step::CornerPermutation => SliceCountTable::<StateCP>::read(&CP_TABLE, StateCP::new(square1).get_index()),
+            Step::EdgePermutation => get_sqsq_count(square1)
+        }
+    }
+}
+
+pub fn load_tables() {
+    SliceCountTable::<StateCO>::read(&CO_TABLE, 0);
+    SliceCountTable::<StateEO>::read(&EO_TABLE, 0);
+    SliceCountTable::<StateCP>::read(&CP_TABLE, 0);
+}
+
+// Ends with the step's last slice and a (0, 0), except for EdgePermutation, which turns the layers into place
+pub fn solve_step(square1: &Square1, step: Step, bar_solved: bool) -> Result<Solution, SolveError> {
+    check_cube_shape(square1)?;
+    if step == Step::EdgePermutation {
+        solve_finish(square1, bar_solved)
+    } else {
+        Ok(solve_substep(square1, &|square1| step.get_count(square1)))
+    }
+}
+
+pub(crate) fn check_cube_shape(square1: &Square1) -> Result<(), SolveError> {
+    square1.validate().map_err(SolveError::Invalid)?;
+    if StateCS::new(square1.clone()).get_cubeshape() != 0 {
+        return Err(SolveError::NotCubeShape)
+    }
+    Ok(())
+}
+
+// Solves the whole Square-1 with the sqsq table, turning the layers into place
+pub(crate) fn solve_finish(square1: &Square1, bar_solved: bool) -> Result<Solution, SolveError> {
+    if square1.parity(bar_solved).overall == Some(true) {
+        return Err(SolveError::OddParity)
+    }
+    let mut slices = get_sqsq_count(square1.clone());
+    if slices.is_multiple_of(2) != bar_solved {
+        slices += 1;
+    }
+    let (_, _, notation) = get_sqsq_finish(square1, slices).expect("Slice Count Tables are wrong");
+    Ok(Solution { notation })
+}
+
+// Solves a step given by its Slice Count Table, ending with the step's last slice and a (0, 0)
+pub(crate) fn solve_substep(square1: &Square1, get_count: &(impl Fn(Square1) -> u8 + Sync)) -> Solution {
+    let slices = get_count(square1.clone());
+    let (_, mut notation) = get_step_path(square1, slices, get_count).expect("Slice Count Tables are wrong");
+    if !notation.is_empty() {
+        notation.push((0, 0));
+    }
+    Solution { notation }
+}
+
+pub fn solve_vandenbergh(square1: Square1, bar_solved: bool) -> Result<Vec<Solution>, SolveError> {
+    let mut square1 = square1;
+    let mut bar_solved = bar_solved;
+    let mut solutions = vec![];
+    for step in STEPS {
+        let solution = solve_step(&square1, step, bar_solved)?;
+        square1.apply(&solution).expect("Step Solution can't be applied");
+        bar_solved ^= solution.slice_count() % 2 == 1;
+        solutions.push(solution);
+    }
+    Ok(solutions)
+}
+
+// Finds the path with the lowest turn value, that finishes the step without leaving cube shape
+fn get_step_path(square1: &Square1, slices: u8, get_count: &(impl Fn(Square1) -> u8 + Sync)) -> Option<(u32, Path)> {
+    if slices == 0 {
+        return Some((0, vec![]))
+    }
+    square1.get_all_turns_sqsq().into_par_iter().filter_map(|turn| {
+        let mut adj = square1.clone();
+        adj.turn_layers(&turn);
+        adj.turn_slice().expect("Couldn't turn slice");
+        if get_count(adj.clone()) < slices {
+            get_step_path(&adj, slices - 1, get_count).map(|(value, mut path)| {
+                let readable = square1.get_human_readable(turn);
+                path.insert(0, readable);
+                (value + get_length(readable) as u32, path)
+            })
+        } else {
+            None
+        }
+    }).min_by_key(|(value, _)| *value)
+}
+
+
+#[cfg(test)]
+mod test {
+    use crate::{solver::SolveError, square1::Square1, state::{statevandenbergh::{StateCO, StateCP, StateEO}, State}};
+
+    use super::{solve_step, Step};
+
+    #[test]
+    pub fn test_vandenbergh() {
+        // Turning the layers doesn't change the step indices
+        let solved = Square1::solved();
+        let mut turned = solved.clone();
+        turned.turn_layers(&(2, 4));
+        assert_eq!(StateCO::new(solved.clone()).get_index(), StateCO::new(turned.clone()).get_index());
+        assert_eq!(StateEO::new(solved.clone()).get_index(), StateEO::new(turned.clone()).get_index());
+        assert_eq!(StateCP::new(solved.clone()).get_index(), StateCP::new(turned.clone()).get_index());
+
+        // Swapping two corners only changes the permutation
+        let swapped = Square1::from_arr([2, 1, 0, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+        assert_eq!(StateCO::new(solved.clone()).get_index(), StateCO::new(swapped.clone()).get_index());
+        assert_eq!(StateEO::new(solved.clone()).get_index(), StateEO::new(swapped.clone()).get_index());
+        assert_ne!(StateCP::new(solved.clone()).get_index(), StateCP::new(swapped).get_index());
+
+        // The steps need cube shape
+        let mut sliced = Square1::solved();
+        sliced.turn_slice().unwrap();
+        assert!(matches!(solve_step(&sliced, Step::CornerOrientation, true), Err(SolveError::NotCubeShape)));
+    }
+}